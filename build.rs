@@ -0,0 +1,4 @@
+fn main() {
+    // Set by coverage tooling on nightly, declared so newer compilers don't warn about it.
+    println!("cargo:rustc-check-cfg=cfg(coverage_nightly)");
+}
@@ -0,0 +1,298 @@
+//! Canonical BBCode output from a [BBParser]'s output, for normalising stored documents.
+//!  Tag names, aliases and argument quoting are normalised, dangling tags are closed and unmatched close tags are removed.
+use alloc::string::String;
+
+use crate::{
+    adapters::{TagAction, TagFilter, TagFilterState},
+    rules::builtin::NoParseRule,
+    BBParser, BBTag, TagArg, TagNamePolicy, TokenKind,
+};
+
+mod sanitize;
+pub use sanitize::*;
+
+/// Serializes a BBCode parse (from [BBParser]) back out to canonical BBCode.
+/// # Idempotency
+/// Serializing the output again, with the same parser configuration, always produces the same output.
+#[derive(Clone, Debug)]
+pub struct BBCodeSerializer<'a> {
+    /// Aliases to rename, as `(alias, canonical name)` pairs.
+    pub aliases: &'a [(&'a str, &'a str)],
+    /// Whether or not to lowercase all tag names.
+    pub lowercase_names: bool,
+    /// Tags whose contents are not parsed, such as code. Their contents are kept exactly as-is.
+    pub noparse_tags: &'a [&'a str],
+}
+
+impl<'a> Default for BBCodeSerializer<'a> {
+    fn default() -> Self {
+        Self {
+            aliases: Self::CORE_ALIASES,
+            lowercase_names: true,
+            noparse_tags: &["code", "pre", "codeblock", "noparse"],
+        }
+    }
+}
+
+impl<'a> BBCodeSerializer<'a> {
+    /// Aliases of the core v1.0.0 tags, mapped onto their shortest name.
+    pub const CORE_ALIASES: &'static [(&'static str, &'static str)] = &[
+        ("bold", "b"),
+        ("italic", "i"),
+        ("underline", "u"),
+        ("under", "u"),
+        ("del", "s"),
+        ("strike", "s"),
+        ("blockquote", "quote"),
+        ("subscript", "sub"),
+        ("super", "sup"),
+        ("superscript", "sup"),
+        ("title", "h1"),
+        ("topic", "h2"),
+        ("subtopic", "h3"),
+        ("codeblock", "pre"),
+    ];
+
+    /// Serialize the given BBCode 'document' back out to canonical BBCode.
+    /// # Remarks
+    /// Unmatched close tags are removed, unless the parser already turned them into text (see [ParserFeature::UNMATCHED_CLOSE_AS_TEXT][crate::ParserFeature::UNMATCHED_CLOSE_AS_TEXT]).
+    pub fn serialize<CustomTy>(&self, parser: BBParser<'_, CustomTy>) -> String
+    where
+        CustomTy: Clone + 'static,
+    {
+        self.serialize_filtered(parser, TagAction::Keep, &|_, _| true, None)
+    }
+
+    /// Serialize the given document, applying the given filter to every tag and only keeping the arguments `keep_arg` accepts.
+    /// Textualized tags are wrapped in `text_wrapper`, which must be a noparse tag, if given.
+    fn serialize_filtered<'b, CustomTy, F>(
+        &self,
+        mut parser: BBParser<'b, CustomTy>,
+        filter: F,
+        keep_arg: &dyn Fn(&str, &TagArg<'_>) -> bool,
+        text_wrapper: Option<&str>,
+    ) -> String
+    where
+        CustomTy: Clone + 'static,
+        F: TagFilter<'b, CustomTy>,
+    {
+        let mut out = String::with_capacity(parser.remaining().len());
+        let policy = parser.config().tag_names;
+        let brackets = parser
+            .config()
+            .brackets
+            .first()
+            .cloned()
+            .unwrap_or(("[", "]"));
+        let (opener, closer) = brackets;
+        let all_brackets = parser.config().brackets;
+        let is_wrapper = |tag: &str| text_wrapper.map_or(false, |x| policy.names_match(x, tag));
+        let mut state = TagFilterState::new(filter).with_policy(policy);
+        // Whether or not we're within a text wrapper, whose contents are written as escaped text.
+        let mut unparsed = false;
+
+        while let Some(tk) = parser.next() {
+            match state.action(&tk) {
+                TagAction::Keep => {}
+                TagAction::Textualize => {
+                    if let Some(wrapper) = text_wrapper {
+                        write_escaped(tk.span, all_brackets, wrapper, brackets, &mut out);
+                    } else {
+                        out.push_str(tk.span);
+                    }
+                    continue;
+                }
+                TagAction::Unwrap | TagAction::Remove => {
+                    // The text on either side of the tag is joined up without it, so it mustn't form a new tag.
+                    seal_openers(all_brackets, text_wrapper, brackets, &mut out);
+                    continue;
+                }
+            }
+
+            match tk.kind {
+                TokenKind::OpenBBTag(BBTag { tag, .. }) if is_wrapper(tag) => {
+                    parser.push_rule(NoParseRule::with_policy(tag, policy));
+                    unparsed = true;
+                }
+                TokenKind::CloseBBTag(_, Some(_)) if unparsed => unparsed = false,
+                _ if unparsed => {
+                    let wrapper = text_wrapper.unwrap();
+                    write_escaped(tk.span, all_brackets, wrapper, brackets, &mut out);
+                }
+                TokenKind::OpenBBTag(BBTag { tag, .. }) => {
+                    let name = self.canonical_name(tag, &policy);
+                    out.push_str(opener);
+                    self.write_name(tag, &policy, &mut out);
+                    write_args(tk.arg_list().filter(|x| keep_arg(name, x)), &mut out);
+                    out.push_str(closer);
+
+                    if self.noparse_tags.iter().any(|x| tk.matches_tag(x, &policy)) {
+                        parser.push_rule(NoParseRule::with_policy(tag, policy));
+                    }
+                }
+                TokenKind::CloseBBTag(_, Some(idx)) => {
+                    // Use the open tag's name, so mismatched case or a generic close comes out the same.
+                    let open = parser.closed_tags()[idx].tag_name().unwrap();
+                    self.write_close(open, &policy, brackets, &mut out);
+                }
+                TokenKind::CloseBBTag(_, None) => {
+                    // Removing the tag could complete an earlier unterminated opener, so leave it as text then.
+                    if out.rfind(opener).map(|x| x + opener.len()) > out.rfind(closer) {
+                        out.push_str(tk.span);
+                    }
+                }
+                TokenKind::StandaloneBBTag(BBTag { tag, .. }) => {
+                    let name = self.canonical_name(tag, &policy);
+                    out.push_str(opener);
+                    self.write_name(tag, &policy, &mut out);
+                    write_args(tk.arg_list().filter(|x| keep_arg(name, x)), &mut out);
+                    if !parser.config().is_void_tag(tag) {
+                        out.push('/');
+                    }
+                    out.push_str(closer);
+                }
+                _ => out.push_str(tk.span),
+            }
+        }
+
+        for (tag, action) in state.open_tags().iter().rev() {
+            // Handle any dangling tags.
+            if *action == TagAction::Keep && !is_wrapper(tag) {
+                self.write_close(tag, &policy, brackets, &mut out);
+            }
+        }
+
+        out
+    }
+
+    /// The given tag name with aliases applied, but not lowercased.
+    fn canonical_name<'t>(&'t self, tag: &'t str, policy: &TagNamePolicy) -> &'t str {
+        self.aliases
+            .iter()
+            .find(|(alias, _)| policy.names_match(alias, tag))
+            .map_or(tag, |(_, canonical)| canonical)
+    }
+
+    /// Write the canonical form of the given tag name.
+    fn write_name(&self, tag: &str, policy: &TagNamePolicy, out: &mut String) {
+        let tag = self.canonical_name(tag, policy);
+
+        if self.lowercase_names {
+            out.extend(tag.chars().flat_map(char::to_lowercase));
+        } else {
+            out.push_str(tag);
+        }
+    }
+
+    fn write_close(
+        &self,
+        tag: &str,
+        policy: &TagNamePolicy,
+        (opener, closer): (&str, &str),
+        out: &mut String,
+    ) {
+        out.push_str(opener);
+        out.push('/');
+        self.write_name(tag, policy, out);
+        out.push_str(closer);
+    }
+}
+
+/// Write the given text such that it stays text when parsed again, by wrapping every opener in it in the given noparse tag.
+/// # Remarks
+/// Only the openers are wrapped, so none of the text can form a tag even if the noparse tag isn't rendered as one.
+fn write_escaped(
+    text: &str,
+    brackets: &[(&str, &str)],
+    wrapper: &str,
+    (opener, closer): (&str, &str),
+    out: &mut String,
+) {
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some((found, _)) = brackets.iter().find(|(x, _)| rest.starts_with(x)) {
+            out.extend([opener, wrapper, closer, found, opener, "/", wrapper, closer]);
+            rest = &rest[found.len()..];
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+}
+
+/// Keep the unterminated openers at the end of the output from forming a tag with whatever is written next, by escaping
+/// them with the given text wrapper, or removing them without one.
+fn seal_openers(
+    brackets: &[(&str, &str)],
+    text_wrapper: Option<&str>,
+    canonical: (&str, &str),
+    out: &mut String,
+) {
+    for (opener, closer) in brackets {
+        let start = out.rfind(closer).map_or(0, |x| x + closer.len());
+        if !out[start..].contains(opener) {
+            continue;
+        }
+
+        let tail = out.split_off(start);
+        match text_wrapper {
+            Some(wrapper) => write_escaped(&tail, &[(opener, closer)], wrapper, canonical, out),
+            None => {
+                // Removing an opener can join the text around it into another.
+                let mut tail = tail;
+                while tail.contains(opener) {
+                    tail = tail.replace(opener, "");
+                }
+                out.push_str(&tail);
+            }
+        }
+    }
+}
+
+/// Write the given tag arguments in canonical form, being `=value` followed by space separated `key=value` pairs.
+/// Arguments whose values can't be written such that they parse back the same are left out.
+fn write_args<'a>(args: impl Iterator<Item = TagArg<'a>>, out: &mut String) {
+    for (idx, TagArg { key, value }) in args.enumerate() {
+        let primary = key.is_none() && idx == 0;
+        let Some(quote) = quote_for(value, primary) else {
+            continue;
+        };
+
+        match key {
+            Some(key) => {
+                out.push(' ');
+                out.extend(key.chars().flat_map(char::to_lowercase));
+                out.push('=');
+            }
+            None if primary => out.push('='),
+            None => out.push(' '),
+        }
+
+        out.extend(quote);
+        out.push_str(value);
+        out.extend(quote);
+    }
+}
+
+/// The quote to write the given argument value in, being `Some(None)` if it survives being parsed again unquoted.
+/// Returns `None` if the value holds both kinds of quote and can't be written unquoted either, as quotes can't be escaped.
+fn quote_for(value: &str, primary: bool) -> Option<Option<char>> {
+    let needs_quotes = value.is_empty()
+        || value.ends_with('/')
+        || value.contains(char::is_whitespace)
+        || (!primary && value.contains('='));
+
+    let (double, single) = (value.contains('"'), value.contains('\''));
+    match (double, single) {
+        // Quotes only start a quoted value at its beginning.
+        (true, true) if !needs_quotes && !value.starts_with(['"', '\'']) => Some(None),
+        (true, true) => None,
+        (true, false) => Some(Some('\'')),
+        (false, true) => Some(Some('"')),
+        (false, false) if needs_quotes => Some(Some('"')),
+        (false, false) => Some(None),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,97 @@
+use alloc::string::String;
+
+use crate::{adapters::TagAction, BBParser, Token};
+
+use super::BBCodeSerializer;
+
+/// A tag allowed by a [Sanitizer], alongside which of its arguments are allowed.
+#[derive(Clone, Copy, Debug)]
+pub struct AllowedTag<'a> {
+    /// The name of the tag, after aliases have been applied.
+    pub name: &'a str,
+    /// The keys of the arguments allowed on this tag, with `""` standing in for values without a key (as in `[tag=value]`).
+    pub args: &'a [&'a str],
+}
+
+impl<'a> AllowedTag<'a> {
+    /// Allow the given tag, without any arguments.
+    pub const fn new(name: &'a str) -> Self {
+        Self { name, args: &[] }
+    }
+
+    /// Allow the given tag, with the given arguments.
+    pub const fn with_args(name: &'a str, args: &'a [&'a str]) -> Self {
+        Self { name, args }
+    }
+}
+
+/// Sanitizes a BBCode parse (from [BBParser]) against an allowlist of tags and arguments, producing canonical BBCode.
+/// # Remarks
+/// Disallowed arguments are removed from allowed tags, and everything else is handled according to [Sanitizer::disallowed].
+#[derive(Clone, Debug)]
+pub struct Sanitizer<'a> {
+    /// The serializer producing the output, and deciding the canonical names tags are matched by.
+    pub serializer: BBCodeSerializer<'a>,
+    /// The tags allowed to remain in the output.
+    pub allowed: &'a [AllowedTag<'a>],
+    /// What to do with all other tags.
+    pub disallowed: TagAction,
+    /// A noparse tag to escape text with, so it stays text when parsed again, as is done for tags kept as text and any
+    /// brackets a removed tag leaves unterminated.
+    /// Only the openers in the text are wrapped, so it stays text even where this tag isn't supported, and this tag's
+    /// contents in the input are escaped the same way.
+    /// Tags kept as text are written verbatim and unterminated brackets are removed if `None`.
+    pub text_wrapper: Option<&'a str>,
+}
+
+impl<'a> Sanitizer<'a> {
+    /// Construct a sanitizer allowing the given tags, unwrapping all others.
+    pub fn new(allowed: &'a [AllowedTag<'a>]) -> Self {
+        Self {
+            serializer: Default::default(),
+            allowed,
+            disallowed: TagAction::Unwrap,
+            text_wrapper: Some("noparse"),
+        }
+    }
+
+    /// Sanitize the given BBCode 'document'.
+    pub fn sanitize<'b, CustomTy>(&self, parser: BBParser<'b, CustomTy>) -> String
+    where
+        CustomTy: Clone + 'static,
+    {
+        let policy = parser.config().tag_names;
+        let allowed = |name: &str| {
+            self.allowed
+                .iter()
+                .find(|x| policy.names_match(x.name, name))
+        };
+
+        let filter = |tk: &Token<'b, CustomTy>| {
+            let name = self
+                .serializer
+                .canonical_name(tk.tag_name().unwrap(), &policy);
+
+            if allowed(name).is_some()
+                || self
+                    .text_wrapper
+                    .map_or(false, |x| policy.names_match(x, name))
+            {
+                TagAction::Keep
+            } else {
+                self.disallowed
+            }
+        };
+
+        let keep_arg = |name: &str, arg: &crate::TagArg<'_>| {
+            allowed(name).map_or(false, |x| {
+                x.args
+                    .iter()
+                    .any(|k| policy.names_match(k, arg.key.unwrap_or("")))
+            })
+        };
+
+        self.serializer
+            .serialize_filtered(parser, filter, &keep_arg, self.text_wrapper)
+    }
+}
@@ -0,0 +1,268 @@
+use alloc::string::String;
+
+use crate::{
+    adapters::TagAction,
+    bbcode::{AllowedTag, BBCodeSerializer, Sanitizer},
+    BBParser, ParserConfig, ParserFeature,
+};
+
+fn canonical(input: &str) -> String {
+    BBCodeSerializer::default().serialize(BBParser::new(input))
+}
+
+#[test]
+pub fn names_and_aliases() {
+    assert_eq!(
+        canonical("[BOLD]a[/bold] [Strike]b[/STRIKE] [ i ]c[/i]"),
+        "[b]a[/b] [s]b[/s] [i]c[/i]"
+    );
+}
+
+#[test]
+pub fn arguments() {
+    assert_eq!(
+        canonical("[quote = 'John Smith'  POST=5 time=\"12:00\"]x[/quote]"),
+        "[quote=\"John Smith\" post=5 time=12:00]x[/quote]"
+    );
+    assert_eq!(
+        canonical("[url=\"https://example.com/?a=b\"]x[/url][code rust]y[/code]"),
+        "[url=https://example.com/?a=b]x[/url][code=rust]y[/code]"
+    );
+    assert_eq!(
+        canonical("[img alt='say \"hi\"'/]"),
+        "[img alt='say \"hi\"'/]"
+    );
+}
+
+#[test]
+pub fn mixed_quotes() {
+    // Quotes can't be escaped, so a value holding both kinds is only kept if it can be written unquoted.
+    let input = "[quote=it's\"x\" name='a \"b\"' title=\"it's\"]x[/quote][quote=it's \"x\" post=5]y[/quote]";
+    let once = canonical(input);
+    assert_eq!(
+        once,
+        "[quote=it's\"x\" name='a \"b\"' title=\"it's\"]x[/quote][quote post=5]y[/quote]"
+    );
+
+    // The kept values parse back exactly as they were.
+    let (kept, original) = (
+        BBParser::new(&once).next().unwrap(),
+        BBParser::new(input).next().unwrap(),
+    );
+    assert!(kept.arg_list().eq(original.arg_list()));
+    assert_eq!(canonical(&once), once);
+}
+
+#[test]
+pub fn dangling_and_unmatched() {
+    assert_eq!(canonical("[b]a[/u][i]b"), "[b]a[i]b[/i][/b]");
+}
+
+#[test]
+pub fn noparse() {
+    assert_eq!(
+        canonical("[CODE][BOLD]x[/BOLD][/code]"),
+        "[code][BOLD]x[/BOLD][/code]"
+    );
+}
+
+#[test]
+pub fn void_tags() {
+    let config = || ParserConfig {
+        void_tags: ParserConfig::COMMON_VOID_TAGS,
+        ..Default::default()
+    };
+    assert_eq!(
+        BBCodeSerializer::default().serialize(BBParser::with_config("[BR /][hr][x/]", config())),
+        "[br][hr][x/]"
+    );
+}
+
+const IDEMPOTENT: &[&str] = &[
+    "[x[/y]]",
+    "[b]x[",
+    "[a[b]c[/b]",
+    "[quote=a=b c=d]x[/quote]",
+    "[url=]x[/url]",
+    "[size=5/]",
+    "[b][i]x[/b]y[/i]",
+    "[b]x[/][/]",
+    "[code]a[/b][/code]",
+    "[img 'a b' c]",
+];
+
+#[test]
+pub fn idempotent() {
+    for config in [
+        || ParserConfig::default(),
+        || ParserConfig {
+            feature_flags: ParserFeature::ALL,
+            void_tags: ParserConfig::COMMON_VOID_TAGS,
+            ..Default::default()
+        },
+    ] {
+        for input in IDEMPOTENT {
+            let serializer = BBCodeSerializer::default();
+            let once = serializer.serialize(BBParser::with_config(input, config()));
+            let twice = serializer.serialize(BBParser::with_config(&once, config()));
+            assert_eq!(once, twice, "{input}");
+        }
+    }
+}
+
+const ALLOWED: &[AllowedTag<'static>] = &[
+    AllowedTag::new("b"),
+    AllowedTag::with_args("quote", &["", "post"]),
+];
+
+#[test]
+pub fn sanitize() {
+    let sanitizer = Sanitizer::new(ALLOWED);
+    let input = "[bold]a[/bold] [script]b[/script] [quote=Me post=5 onclick=x]c[/quote] [b=x]d";
+
+    assert_eq!(
+        sanitizer.sanitize(BBParser::new(input)),
+        "[b]a[/b] b [quote=Me post=5]c[/quote] [b]d[/b]"
+    );
+}
+
+#[test]
+pub fn sanitize_policies() {
+    let input = "x[script]alert([b]1[/b])[/script]y";
+
+    let mut sanitizer = Sanitizer::new(ALLOWED);
+    sanitizer.disallowed = TagAction::Remove;
+    assert_eq!(sanitizer.sanitize(BBParser::new(input)), "xy");
+
+    sanitizer.disallowed = TagAction::Textualize;
+    let once = sanitizer.sanitize(BBParser::new(input));
+    assert_eq!(
+        once,
+        "x[noparse][[/noparse]script]alert([b]1[/b])[noparse][[/noparse]/script]y"
+    );
+    assert_eq!(sanitizer.sanitize(BBParser::new(&once)), once);
+}
+
+/// Assert the given sanitized output only holds tags from [ALLOWED] when parsed again, outside of noparse tags.
+fn assert_only_allowed(output: &str) {
+    let mut unparsed = false;
+    for tk in BBParser::new(output) {
+        let Some(name) = tk.tag_name() else {
+            continue;
+        };
+        if name == "noparse" {
+            unparsed = matches!(tk.kind, crate::TokenKind::OpenBBTag(_));
+        } else {
+            assert!(
+                unparsed || ALLOWED.iter().any(|x| x.name == name),
+                "{name} in {output}"
+            );
+        }
+    }
+}
+
+#[test]
+pub fn sanitize_removed_joins() {
+    const INPUTS: &[&str] = &[
+        "[[u]url=https://evil[/u]]click[[u]/url[/u]]",
+        "[[/x]img]https://evil/a.png[[/x]/img]",
+        "[[/size]]",
+        "[a [u]b[/u]]",
+        "[noparse][url=x][/noparse][/url]",
+    ];
+
+    for disallowed in [TagAction::Unwrap, TagAction::Remove] {
+        for wrapper in [Some("noparse"), None] {
+            let mut sanitizer = Sanitizer::new(ALLOWED);
+            sanitizer.disallowed = disallowed;
+            sanitizer.text_wrapper = wrapper;
+
+            for input in INPUTS {
+                let once = sanitizer.sanitize(BBParser::new(input));
+                assert_only_allowed(&once);
+                assert_eq!(sanitizer.sanitize(BBParser::new(&once)), once, "{input}");
+            }
+        }
+    }
+
+    let sanitizer = Sanitizer::new(ALLOWED);
+    assert_eq!(
+        sanitizer.sanitize(BBParser::new(INPUTS[0])),
+        "[noparse][[/noparse]url=https://evil]click[noparse][[/noparse]/url]"
+    );
+    assert_eq!(
+        sanitizer.sanitize(BBParser::new("[[/size]]")),
+        "[noparse][[/noparse]]"
+    );
+}
+
+#[test]
+pub fn sanitize_arg_policy() {
+    use crate::TagNamePolicy;
+
+    let config = ParserConfig {
+        tag_names: TagNamePolicy {
+            case_sensitive: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let sanitizer = Sanitizer::new(ALLOWED);
+    assert_eq!(
+        sanitizer.sanitize(BBParser::with_config(
+            "[quote POST=1 post=2]x[/quote]",
+            config
+        )),
+        "[quote post=2]x[/quote]"
+    );
+}
+
+#[test]
+pub fn sanitize_noparse_contents() {
+    // Code isn't allowed, so its contents must be sanitized rather than passed through.
+    let sanitizer = Sanitizer::new(ALLOWED);
+    assert_eq!(
+        sanitizer.sanitize(BBParser::new("[code][script]x[/script][/code]")),
+        "x"
+    );
+}
+
+#[cfg(feature = "html_gen")]
+const NOPARSE_BYPASS: &str =
+    "[noparse][url=https://evil.example]click[/url][img]https://x/y.png[/img][/noparse]";
+
+#[cfg(feature = "html_gen")]
+#[test]
+pub fn sanitize_noparse_rendered() {
+    use crate::html::{builtins, HtmlSerializer, SimpleHtmlWriter};
+
+    // The noparse tag's contents are escaped, so they stay text even where noparse isn't supported.
+    let sanitized = Sanitizer::new(&[AllowedTag::new("b")]).sanitize(BBParser::new(NOPARSE_BYPASS));
+    assert_eq!(
+        sanitized,
+        "[noparse][[/noparse]url=https://evil.example]click[noparse][[/noparse]/url]\
+         [noparse][[/noparse]img]https://x/y.png[noparse][[/noparse]/img]"
+    );
+
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+    assert_eq!(
+        serializer.serialize(BBParser::new(&sanitized)),
+        "[url=https:&#x2F;&#x2F;evil.example]click[&#x2F;url][img]https:&#x2F;&#x2F;x&#x2F;y.png[&#x2F;img]"
+    );
+}
+
+#[cfg(feature = "markdown_gen")]
+#[test]
+pub fn sanitize_noparse_rendered_markdown() {
+    use crate::markdown::{builtins, MarkdownSerializer, SimpleMarkdownWriter};
+
+    let input = "[noparse][url=https://evil.example]click[/url][/noparse]";
+    let sanitized = Sanitizer::new(&[AllowedTag::new("b")]).sanitize(BBParser::new(input));
+
+    let mut serializer =
+        MarkdownSerializer::<SimpleMarkdownWriter>::with_tags(builtins::all_tags());
+    assert_eq!(
+        serializer.serialize(BBParser::new(&sanitized)),
+        "\\[url=https://evil.example\\]click\\[/url\\]"
+    );
+}
@@ -1,16 +1,33 @@
 //! Built-in implementations of common (i.e. used by many implementations, subjectively) BBCode tags.
 
-use super::HtmlTagWriter;
+use super::{Html, TagWriter};
+use crate::render::Newlines;
 
+mod code;
+mod image;
 mod link;
+mod list;
+mod noparse;
+mod quote;
 mod simple;
+mod spoiler;
+mod style;
+mod table;
+pub use code::*;
+pub use image::*;
 pub use link::*;
+pub use list::*;
+pub use noparse::*;
+pub use quote::*;
 pub use simple::*;
+pub use spoiler::*;
+pub use style::*;
+pub use table::*;
 
 macro_rules! tag_list {
     ($ct:ident; $($tag:ident),*) => {
         {
-            let v: Vec<Box<dyn HtmlTagWriter<$ct>>> = vec![
+            let v: Vec<Box<dyn TagWriter<Html, $ct>>> = vec![
                 $(
                     Box::new($tag::default()),
                 )*
@@ -41,10 +58,10 @@ macro_rules! tag_list {
 /// - [Header5Tag]
 /// - [Header6Tag]
 /// - [CenterTag]
-/// - [CodeTag]
-/// - [PreformattedTag]
-/// - [KbdTag]
-pub fn all_core_v1_tags<CustomTy>() -> Vec<Box<dyn HtmlTagWriter<CustomTy>>>
+/// - [ListTag]
+/// - [ListItemTag]
+/// - [NoParseTag]
+pub fn all_core_v1_tags<CustomTy>() -> Vec<Box<dyn TagWriter<Html, CustomTy>>>
 where
     CustomTy: Clone + Default + 'static,
 {
@@ -64,6 +81,49 @@ where
         Header4Tag,
         Header5Tag,
         Header6Tag,
-        CenterTag
+        CenterTag,
+        ListTag,
+        ListItemTag,
+        NoParseTag
+    }
+}
+
+/// Returns every built-in HTML tag, being [all_core_v1_tags] alongside alignment, code, links, images, colors, sizes, fonts, tables and spoilers.
+/// # Remarks
+/// Links and images are only written for URLs allowed by [LinkTag] and [ImageTag], using their default settings, otherwise
+/// their text is written instead.
+pub fn all_tags<CustomTy>() -> Vec<Box<dyn TagWriter<Html, CustomTy>>>
+where
+    CustomTy: Clone + Default + 'static,
+{
+    let mut tags = all_core_v1_tags();
+    tags.append(&mut tag_list! {CustomTy;
+        LeftTag,
+        RightTag,
+        KbdTag,
+        CodeTag,
+        PreformattedTag,
+        LinkTag,
+        ImageTag,
+        ColorTag,
+        SizeTag,
+        FontTag,
+        TableTag,
+        TableRowTag,
+        TableCellTag,
+        SpoilerTag
+    });
+    tags
+}
+
+/// Returns the newline handling for HTML, see [Renderer::set_newlines][crate::render::Renderer::set_newlines].
+/// Single newlines are written as [LinebreakTag], and blank lines split paragraphs written as [ParagraphTag].
+pub fn newlines<CustomTy>() -> Newlines<Html, CustomTy>
+where
+    CustomTy: Clone + Default + 'static,
+{
+    Newlines {
+        line_break: Box::new(LinebreakTag::default()),
+        paragraph: Some(Box::new(ParagraphTag::default())),
     }
 }
@@ -0,0 +1,197 @@
+use core::fmt;
+
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    render::ContentModel,
+    rules::{builtin::NoParseRule, ParserRule, ParserRuleObjBox},
+    BBTag, Token, TokenKind,
+};
+
+/// The language given to the code tag, in lowercase, or `Err` if it can't be written as a class name.
+fn language<CustomTy: Clone>(token: &Token<'_, CustomTy>) -> Result<Option<String>, ()> {
+    let args = token.arg_list();
+    let Some(language) = args.value().or_else(|| args.get("lang")).map(str::trim) else {
+        return Ok(None);
+    };
+
+    if language.is_empty()
+        || !language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '#' | '.' | '_'))
+    {
+        return Err(());
+    }
+
+    Ok(Some(language.to_ascii_lowercase()))
+}
+
+macro_rules! code_tag_writer {
+    ($name:ident, $tags:expr, $content:ident, $open:expr, $close:expr) => {
+        impl<CustomTy> TagWriter<Html, CustomTy> for $name<CustomTy>
+        where
+            CustomTy: Clone + 'static,
+        {
+            fn match_tag(&self, tag: &str) -> bool {
+                self.match_tag_with(tag, &Default::default())
+            }
+
+            fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+                $tags.iter().any(|x| policy.names_match(x, tag))
+            }
+
+            fn open_tag(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                token: &Token<'_, CustomTy>,
+                out: &mut HtmlOutput<'_>,
+            ) -> Result<bool, fmt::Error> {
+                // Code always takes its contents, so this is only reached if the renderer can't provide them.
+                tk_writer.write_token(token, out)?;
+                Ok(false)
+            }
+
+            fn close_tag<'a>(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                _: &Token<'a, CustomTy>,
+                close_token: &Token<'a, CustomTy>,
+                out: &mut HtmlOutput<'_>,
+            ) -> fmt::Result {
+                tk_writer.write_token(close_token, out)
+            }
+
+            fn standalone_tag(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                token: &Token<'_, CustomTy>,
+                out: &mut HtmlOutput<'_>,
+            ) -> fmt::Result {
+                tk_writer.write_token(token, out)
+            }
+
+            fn takes_contents(&self, _: &Token<'_, CustomTy>) -> bool {
+                true
+            }
+
+            fn contents_tag<'a>(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                open_token: &Token<'a, CustomTy>,
+                contents: &Token<'a, CustomTy>,
+                close_token: Option<&Token<'a, CustomTy>>,
+                out: &mut HtmlOutput<'_>,
+            ) -> fmt::Result {
+                let Ok(language) = language(open_token) else {
+                    tk_writer.write_token(open_token, out)?;
+                    tk_writer.write_token(contents, out)?;
+                    return match close_token {
+                        Some(close_token) => tk_writer.write_token(close_token, out),
+                        None => Ok(()),
+                    };
+                };
+
+                let open: fn(Option<&str>, &mut HtmlOutput<'_>) -> fmt::Result = $open;
+                let close: fn(Option<&str>, &mut HtmlOutput<'_>) -> fmt::Result = $close;
+                open(language.as_deref(), out)?;
+                tk_writer.write_token(contents, out)?;
+                close(language.as_deref(), out)
+            }
+
+            fn content_model(&self, _: &Token<'_, CustomTy>) -> ContentModel {
+                ContentModel::$content
+            }
+
+            fn try_special<'a>(
+                &self,
+                token: &Token<'a, CustomTy>,
+            ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+                self.try_special_with(token, &Default::default())
+            }
+
+            fn try_special_with<'a>(
+                &self,
+                token: &Token<'a, CustomTy>,
+                policy: &crate::TagNamePolicy,
+            ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+                match token.kind {
+                    TokenKind::OpenBBTag(BBTag { tag, .. }) => {
+                        Some(NoParseRule::with_policy(tag, *policy).to_box())
+                    }
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+/// Write an opening `<code>`, with a class for the given language, if any.
+fn write_code_open(language: Option<&str>, out: &mut HtmlOutput<'_>) -> fmt::Result {
+    match language {
+        Some(language) => write!(out, "<code class=\"language-{language}\">"),
+        None => out.write_str("<code>"),
+    }
+}
+
+/// Inline code tag, which converts to HTML5 `<code>`. The contents are not parsed, and are kept exactly as they are.
+/// <br/>
+/// This matches the following BBCode tags: `["code"]`
+/// # Arguments
+/// The language as the value, or `lang`, written as a `language-` class.
+/// # Exact output
+/// `[code=rust]let x = 1;[/code]` converts exactly to
+/// ```html
+/// <code class="language-rust">let x = 1;</code>
+/// ```
+/// # Remarks
+/// The language is lowercased, and may only contain ASCII letters, digits, and `+-#._`. Otherwise the tag is written as text.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CodeTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+code_tag_writer!(
+    CodeTag,
+    ["code"],
+    VERBATIM_INLINE,
+    write_code_open,
+    |_, out| out.write_str("</code>")
+);
+
+/// Block code tag, which converts to HTML5 `<pre>`, wrapping a `<code>` when given a language. The contents are not parsed,
+/// and are kept exactly as they are.
+/// <br/>
+/// This matches the following BBCode tags: `["pre", "codeblock"]`
+/// # Arguments
+/// The language as the value, or `lang`, written as a `language-` class.
+/// # Exact output
+/// `[pre=rust]fn main() {}[/pre]` converts exactly to
+/// ```html
+/// <pre><code class="language-rust">fn main() {}</code></pre>
+/// ```
+/// Without a language, it converts exactly to
+/// ```html
+/// <pre>contents</pre>
+/// ```
+/// # Remarks
+/// The language is lowercased, and may only contain ASCII letters, digits, and `+-#._`. Otherwise the tag is written as text.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PreformattedTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+code_tag_writer!(
+    PreformattedTag,
+    ["pre", "codeblock"],
+    VERBATIM_BLOCK,
+    |language, out| match language {
+        Some(_) => {
+            out.write_str("<pre>")?;
+            write_code_open(language, out)
+        }
+        None => out.write_str("<pre>"),
+    },
+    |language, out| match language {
+        Some(_) => out.write_str("</code></pre>"),
+        None => out.write_str("</pre>"),
+    }
+);
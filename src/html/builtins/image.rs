@@ -0,0 +1,222 @@
+use core::fmt;
+
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    render::ContentModel,
+    rules::{builtin::NoParseRule, ParserRule, ParserRuleObjBox},
+    BBTag, Token, TokenKind,
+};
+
+use super::sanitize_url;
+
+/// Rewrites a checked URL, returning `None` to reject it, see [ImageTag::rewrite_url].
+pub type UrlRewriter = Box<dyn Fn(&str) -> Option<String>>;
+
+/// Image tag, which converts to HTML5 `<img>`, taking its URL from its contents. The contents are not parsed.
+/// <br/>
+/// This matches the following BBCode tags: `["img", "image"]`
+/// # Arguments
+/// - A value of the form `WxH`, such as `[img=100x50]`, giving the width and height.
+/// - `width` and `height`, giving either dimension alone.
+/// - `alt`, the image's alt text, which defaults to [ImageTag::fallback_alt].
+/// # Exact output
+/// `[img=100x50 alt=Cat]https://example.com/cat.png[/img]` converts exactly to
+/// ```html
+/// <img src="https://example.com/cat.png" alt="Cat" width="100" height="50" loading="lazy"/>
+/// ```
+/// # Remarks
+/// Only relative URLs and those with one of [ImageTag::schemes] are shown, and dimensions must be positive whole numbers.
+/// Otherwise the tag is written as text. Dimensions over the maximums are clamped to them.
+pub struct ImageTag {
+    /// The URL schemes which images may be loaded from, compared ignoring ASCII case.
+    /// Defaults to `http` and `https`.
+    pub schemes: Vec<String>,
+    /// The largest width written, in pixels. Defaults to 2048.
+    pub max_width: u32,
+    /// The largest height written, in pixels. Defaults to 2048.
+    pub max_height: u32,
+    /// The alt text of images not given one. Defaults to `Image`.
+    pub fallback_alt: String,
+    /// Rewrites the URL of each image once it's been checked, such as to load it through a proxy.
+    /// Returning `None` writes the tag as text instead.
+    pub rewrite_url: Option<UrlRewriter>,
+}
+
+impl Default for ImageTag {
+    fn default() -> Self {
+        Self {
+            schemes: ["http", "https"].map(String::from).to_vec(),
+            max_width: 2048,
+            max_height: 2048,
+            fallback_alt: String::from("Image"),
+            rewrite_url: None,
+        }
+    }
+}
+
+impl fmt::Debug for ImageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageTag")
+            .field("schemes", &self.schemes)
+            .field("max_width", &self.max_width)
+            .field("max_height", &self.max_height)
+            .field("fallback_alt", &self.fallback_alt)
+            .field("rewrite_url", &self.rewrite_url.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl ImageTag {
+    /// Make the given URL safe to load an image from, returning `None` if it may not be loaded.
+    /// # Remarks
+    /// See [sanitize_url] for what's removed from the URL, [ImageTag::rewrite_url] is applied afterwards.
+    pub fn sanitize_url(&self, url: &str) -> Option<String> {
+        let url = sanitize_url(url, &self.schemes)?;
+
+        match &self.rewrite_url {
+            Some(rewrite) => rewrite(&url),
+            None => Some(url),
+        }
+    }
+
+    /// The width and height given to the image, clamped to the maximums, or `Err` if either is invalid.
+    #[allow(clippy::result_unit_err)]
+    pub fn dimensions<CustomTy: Clone>(
+        &self,
+        token: &Token<'_, CustomTy>,
+    ) -> Result<(Option<u32>, Option<u32>), ()> {
+        let args = token.arg_list();
+        let (width, height) = match args.value() {
+            Some(value) => {
+                let (width, height) = value.split_once(['x', 'X']).ok_or(())?;
+                (Some(width), Some(height))
+            }
+            None => (args.get("width"), args.get("height")),
+        };
+
+        let parse = |value: Option<&str>, max: u32| match value {
+            Some(value) => match value.trim().parse::<u32>() {
+                Ok(0) | Err(_) => Err(()),
+                Ok(x) => Ok(Some(x.min(max))),
+            },
+            None => Ok(None),
+        };
+
+        Ok((
+            parse(width, self.max_width)?,
+            parse(height, self.max_height)?,
+        ))
+    }
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for ImageTag
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        ["img", "image"].iter().any(|x| x.eq_ignore_ascii_case(tag))
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["img", "image"].iter().any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        // Images always take their contents, so this is only reached if the renderer can't provide them.
+        tk_writer.write_token(token, out)?;
+        Ok(false)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        close_token: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(close_token, out)
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn takes_contents(&self, _: &Token<'_, CustomTy>) -> bool {
+        true
+    }
+
+    fn contents_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        contents: &Token<'a, CustomTy>,
+        close_token: Option<&Token<'a, CustomTy>>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        let (Some(url), Ok((width, height))) = (
+            self.sanitize_url(contents.span),
+            self.dimensions(open_token),
+        ) else {
+            tk_writer.write_token(open_token, out)?;
+            tk_writer.write_token(contents, out)?;
+            return match close_token {
+                Some(close_token) => tk_writer.write_token(close_token, out),
+                None => Ok(()),
+            };
+        };
+
+        let alt = open_token
+            .arg_list()
+            .get("alt")
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .unwrap_or(&self.fallback_alt);
+
+        out.write_str("<img src=\"")?;
+        out.write_str(&html_escape::encode_double_quoted_attribute(&url))?;
+        out.write_str("\" alt=\"")?;
+        out.write_str(&html_escape::encode_double_quoted_attribute(alt))?;
+        out.write_str("\"")?;
+        if let Some(width) = width {
+            write!(out, " width=\"{width}\"")?;
+        }
+        if let Some(height) = height {
+            write!(out, " height=\"{height}\"")?;
+        }
+        out.write_str(" loading=\"lazy\"/>")
+    }
+
+    fn content_model(&self, _: &Token<'_, CustomTy>) -> ContentModel {
+        ContentModel::INLINE
+    }
+
+    fn try_special<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        self.try_special_with(token, &Default::default())
+    }
+
+    fn try_special_with<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+        policy: &crate::TagNamePolicy,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        match token.kind {
+            TokenKind::OpenBBTag(BBTag { tag, .. }) => {
+                Some(NoParseRule::with_policy(tag, *policy).to_box())
+            }
+            _ => None,
+        }
+    }
+}
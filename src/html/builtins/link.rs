@@ -1,2 +1,203 @@
-pub struct LinkTag;
+use core::fmt;
 
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    render::ContentModel,
+    rules::{builtin::NoParseRule, ParserRule, ParserRuleObjBox},
+    BBTag, Token, TokenKind,
+};
+
+/// Link tag, which converts to HTML5 `<a>`, taking its URL from its value or, if given none, its contents.
+/// <br/>
+/// This matches the following BBCode tags: `["url", "link"]`
+/// # Exact output
+/// `[url=https://example.com]label[/url]` converts exactly to
+/// ```html
+/// <a href="https://example.com" rel="nofollow ugc noopener">label</a>
+/// ```
+/// # Remarks
+/// Only relative URLs and those with one of [LinkTag::schemes] are linked, `javascript:` URLs never are. Otherwise the tag
+/// is written as text. Without a value, the contents are not parsed.
+#[derive(Clone, Debug)]
+pub struct LinkTag {
+    /// The URL schemes which may be linked to, compared ignoring ASCII case.
+    /// Defaults to `http`, `https` and `mailto`.
+    pub schemes: Vec<String>,
+    /// The `rel` attribute of links, if any. Defaults to `nofollow ugc noopener`, as links come from users.
+    pub rel: Option<String>,
+    /// The `target` attribute of links, if any, such as `_blank`. Defaults to none.
+    pub target: Option<String>,
+}
+
+impl Default for LinkTag {
+    fn default() -> Self {
+        Self {
+            schemes: ["http", "https", "mailto"].map(String::from).to_vec(),
+            rel: Some(String::from("nofollow ugc noopener")),
+            target: None,
+        }
+    }
+}
+
+impl LinkTag {
+    /// Make the given URL safe to link to, returning `None` if it may not be linked to.
+    /// # Remarks
+    /// See [sanitize_url] for what's removed from the URL.
+    pub fn sanitize_url(&self, url: &str) -> Option<String> {
+        sanitize_url(url, &self.schemes)
+    }
+
+    /// Write the opening `<a>` for the given URL, which must already be sanitized.
+    fn write_open(&self, url: &str, out: &mut HtmlOutput<'_>) -> fmt::Result {
+        out.write_str("<a href=\"")?;
+        out.write_str(&html_escape::encode_double_quoted_attribute(url))?;
+        out.write_str("\"")?;
+
+        if let Some(rel) = &self.rel {
+            out.write_str(" rel=\"")?;
+            out.write_str(&html_escape::encode_double_quoted_attribute(rel))?;
+            out.write_str("\"")?;
+        }
+
+        if let Some(target) = &self.target {
+            out.write_str(" target=\"")?;
+            out.write_str(&html_escape::encode_double_quoted_attribute(target))?;
+            out.write_str("\"")?;
+        }
+
+        out.write_str(">")
+    }
+}
+
+/// Make the given URL safe to use in an attribute, returning `None` unless it's relative or has one of the given schemes.
+/// `javascript:` URLs are never allowed.
+/// # Remarks
+/// Surrounding whitespace is trimmed. URLs still holding whitespace, control characters or brackets are never allowed,
+/// as browsers would remove some of them before reading the scheme, and they're usually text that was never a URL.
+pub fn sanitize_url(url: &str, schemes: &[String]) -> Option<String> {
+    let url = url.trim_matches(|c: char| c.is_whitespace() || c.is_control());
+
+    if url.is_empty()
+        || url.contains(|c: char| c.is_whitespace() || c.is_control() || matches!(c, '[' | ']'))
+    {
+        return None;
+    }
+
+    match url_scheme(url) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("javascript") => None,
+        Some(scheme) if !schemes.iter().any(|x| x.eq_ignore_ascii_case(scheme)) => None,
+        _ => Some(url.into()),
+    }
+}
+
+/// The scheme of the given URL, if it has one.
+fn url_scheme(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_once(':')?;
+
+    (scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')))
+    .then_some(scheme)
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for LinkTag
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        ["url", "link"].iter().any(|x| x.eq_ignore_ascii_case(tag))
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["url", "link"].iter().any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        match token.arg_list().value().and_then(|x| self.sanitize_url(x)) {
+            Some(url) => self.write_open(&url, out).map(|_| true),
+            None => tk_writer.write_token(token, out).map(|_| false),
+        }
+    }
+
+    fn close_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        close_token: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        match open_token
+            .arg_list()
+            .value()
+            .and_then(|x| self.sanitize_url(x))
+        {
+            Some(_) => out.write_str("</a>"),
+            None => tk_writer.write_token(close_token, out),
+        }
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn takes_contents(&self, token: &Token<'_, CustomTy>) -> bool {
+        token.arg_list().value().is_none()
+    }
+
+    fn contents_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        contents: &Token<'a, CustomTy>,
+        close_token: Option<&Token<'a, CustomTy>>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        let Some(url) = self.sanitize_url(contents.span) else {
+            tk_writer.write_token(open_token, out)?;
+            tk_writer.write_token(contents, out)?;
+            return match close_token {
+                Some(close_token) => tk_writer.write_token(close_token, out),
+                None => Ok(()),
+            };
+        };
+
+        self.write_open(&url, out)?;
+        tk_writer.write_token(contents, out)?;
+        out.write_str("</a>")
+    }
+
+    fn content_model(&self, _: &Token<'_, CustomTy>) -> ContentModel {
+        ContentModel::INLINE
+    }
+
+    fn try_special<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        self.try_special_with(token, &Default::default())
+    }
+
+    fn try_special_with<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+        policy: &crate::TagNamePolicy,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        match token.kind {
+            TokenKind::OpenBBTag(BBTag { tag, .. }) if self.takes_contents(token) => {
+                Some(NoParseRule::with_policy(tag, *policy).to_box())
+            }
+            _ => None,
+        }
+    }
+}
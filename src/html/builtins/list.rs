@@ -0,0 +1,172 @@
+use core::fmt;
+
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    render::ContentModel,
+    Token,
+};
+
+/// The list kind written for the given list tag, being `None` for a plain list, or `Some` with the `<ol>` attributes.
+/// Returns `Err` for an unknown list style, which is written as text.
+fn list_kind<CustomTy: Clone>(token: &Token<'_, CustomTy>) -> Result<Option<String>, ()> {
+    let ordered = token.matches_tag("ol", &Default::default());
+
+    match token.arg_list().value().map(str::trim) {
+        None if ordered => Ok(Some(String::new())),
+        None => Ok(None),
+        Some(style @ ("a" | "A" | "i" | "I")) => Ok(Some(format!(" type=\"{style}\""))),
+        Some(start) => match start.parse::<u32>() {
+            Ok(1) => Ok(Some(String::new())),
+            Ok(start) => Ok(Some(format!(" start=\"{start}\""))),
+            Err(_) => Err(()),
+        },
+    }
+}
+
+/// List tag, which converts to HTML5 `<ul>`, or `<ol>` for numbered lists.
+/// <br/>
+/// This matches the following BBCode tags: `["list", "ul", "ol"]`
+/// # Arguments
+/// The numbering as the value: `1` (or any other starting number), `a`, `A`, `i` or `I`. `[ol]` is numbered from `1`.
+/// # Exact output
+/// `[list=a][*]one[*]two[/list]` converts exactly to
+/// ```html
+/// <ol type="a"><li>one</li><li>two</li></ol>
+/// ```
+/// # Remarks
+/// Items are started by [ListItemTag]. `[*]` is usually left unclosed, so items end at the next item or the end of the
+/// list, whether or not it's configured as a void tag (see [ParserConfig::void_tags][crate::ParserConfig::void_tags]).
+/// Any other numbering is written as text.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ListTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for ListTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["list", "ul", "ol"]
+            .iter()
+            .any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        match list_kind(token) {
+            Ok(Some(attrs)) => write!(out, "<ol{attrs}>").map(|_| true),
+            Ok(None) => out.write_str("<ul>").map(|_| true),
+            Err(()) => tk_writer.write_token(token, out).map(|_| false),
+        }
+    }
+
+    fn close_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        close_token: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        match list_kind(open_token) {
+            Ok(Some(_)) => out.write_str("</ol>"),
+            Ok(None) => out.write_str("</ul>"),
+            Err(()) => tk_writer.write_token(close_token, out),
+        }
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn content_model(&self, token: &Token<'_, CustomTy>) -> ContentModel {
+        match list_kind(token) {
+            Ok(_) => ContentModel::BLOCK.with_role("list"),
+            // Written as text, so it can't hold items.
+            Err(()) => ContentModel::INLINE,
+        }
+    }
+}
+
+/// List item tag, which converts to HTML5 `<li>` within a [ListTag]. Outside of a list it is written as text.
+/// <br/>
+/// This matches the following BBCode tags: `["*", "li"]`
+/// # Exact output
+/// This tag converts exactly to
+/// ```html
+/// <li>contents</li>
+/// ```
+/// # Remarks
+/// An unclosed `[*]` lasts until the next item or the end of the list, whether or not it's a standalone tag.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ListItemTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for ListItemTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["*", "li"].iter().any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        match token.args() {
+            None => out.write_str("<li>").map(|_| true),
+            Some(_) => tk_writer.write_token(token, out).map(|_| false),
+        }
+    }
+
+    fn close_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        close_token: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        match open_token.args() {
+            None => out.write_str("</li>"),
+            Some(_) => tk_writer.write_token(close_token, out),
+        }
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        // Standalone items are opened until the next one instead, see [ContentModel::parent].
+        tk_writer.write_token(token, out)
+    }
+
+    fn content_model(&self, token: &Token<'_, CustomTy>) -> ContentModel {
+        match token.args() {
+            None => ContentModel::BLOCK.within("list"),
+            Some(_) => ContentModel::INLINE,
+        }
+    }
+}
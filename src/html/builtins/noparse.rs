@@ -0,0 +1,84 @@
+use core::fmt;
+
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    rules::{builtin::NoParseRule, ParserRule, ParserRuleObjBox},
+    BBTag, Token, TokenKind,
+};
+
+/// No parse tag, which writes its contents as text exactly as they are, without any markup of its own.
+/// <br/>
+/// This matches the following BBCode tags: `["noparse"]`
+/// # Exact output
+/// `[noparse][b]contents[/b][/noparse]` converts exactly to
+/// ```html
+/// [b]contents[&#x2F;b]
+/// ```
+/// # Remarks
+/// This is the tag the [Sanitizer][crate::bbcode::Sanitizer] wraps tags kept as text in, so they must be rendered by it
+/// to stay text. Any arguments are ignored.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoParseTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for NoParseTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        policy.names_match("noparse", tag)
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Html, CustomTy>,
+        _: &Token<'_, CustomTy>,
+        _: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Html, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        Ok(())
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn try_special<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        self.try_special_with(token, &Default::default())
+    }
+
+    fn try_special_with<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+        policy: &crate::TagNamePolicy,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        match token.kind {
+            TokenKind::OpenBBTag(BBTag { tag, .. }) => {
+                Some(NoParseRule::with_policy(tag, *policy).to_box())
+            }
+            _ => None,
+        }
+    }
+}
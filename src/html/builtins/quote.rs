@@ -0,0 +1,100 @@
+use core::fmt;
+
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    render::ContentModel,
+    Token,
+};
+
+/// Block quote tag, which converts to HTML5 `<blockquote>`, headed by a `<cite>` naming who's quoted.
+/// <br/>
+/// This matches the following BBCode tags: `["quote", "blockquote"]`
+/// # Arguments
+/// - The name of who's quoted as the value, or `name`.
+/// - `post`, the quoted post, written as `data-post`.
+/// - `time`, when the quoted post was made, written as `data-time`.
+/// # Exact output
+/// `[quote="John Smith" post=5]contents[/quote]` converts exactly to
+/// ```html
+/// <blockquote data-post="5"><cite>John Smith</cite>contents</blockquote>
+/// ```
+/// Without arguments, it converts exactly to
+/// ```html
+/// <blockquote>contents</blockquote>
+/// ```
+/// # Remarks
+/// Every value is escaped, and other arguments are ignored.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BlockQuoteTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for BlockQuoteTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["quote", "blockquote"]
+            .iter()
+            .any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        fn non_empty(x: Option<&str>) -> Option<&str> {
+            x.map(str::trim).filter(|x| !x.is_empty())
+        }
+        let args = token.arg_list();
+
+        out.write_str("<blockquote")?;
+        for (attr, key) in [("data-post", "post"), ("data-time", "time")] {
+            if let Some(value) = non_empty(args.get(key)) {
+                write!(
+                    out,
+                    " {attr}=\"{}\"",
+                    html_escape::encode_double_quoted_attribute(value)
+                )?;
+            }
+        }
+        out.write_str(">")?;
+
+        if let Some(name) = non_empty(args.value().or_else(|| args.get("name"))) {
+            out.write_str("<cite>")?;
+            out.write_str(&html_escape::encode_safe(name))?;
+            out.write_str("</cite>")?;
+        }
+
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Html, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        out.write_str("</blockquote>")
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn content_model(&self, _: &Token<'_, CustomTy>) -> ContentModel {
+        ContentModel::BLOCK
+    }
+}
@@ -1,52 +1,70 @@
-use crate::html::HtmlTagWriter;
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    render::ContentModel,
+};
 
-impl<T: SimpleHtmlTagWriter<CustomTy>, CustomTy> HtmlTagWriter<CustomTy> for T
-where
-    CustomTy: Clone + 'static,
-{
-    fn match_tag(&self, tag: &str) -> bool {
-        Self::TAGS.iter().any(|x| x.eq_ignore_ascii_case(tag))
-    }
+/// Shared [TagWriter] implementation of the tags declared with `simple_tag!` and `simple_standalone_tag!`.
+/// # Remarks
+/// This can't be a blanket implementation, as it would conflict with the other tags'.
+struct Simple<T>(core::marker::PhantomData<T>);
 
-    fn open_tag(
-        &self,
-        tk_writer: &dyn crate::html::HtmlTokenWriter<CustomTy>,
+impl<T> Simple<T> {
+    fn open_tag<CustomTy>(
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
         token: &crate::Token<'_, CustomTy>,
-        out: &mut String,
-    ) {
-        // TODO: This could use let expressions when those are stable late-bound.
-        if Self::TAGS.iter().any(|x| token.is_open_argless(x)) && Self::HTML_OPEN.is_some() {
-            out.push_str(Self::HTML_OPEN.unwrap())
-        } else {
-            tk_writer.write_token(token, out)
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, core::fmt::Error>
+    where
+        CustomTy: Clone,
+        T: SimpleHtmlTagWriter<CustomTy>,
+    {
+        // The serializer has already matched the tag name, so only the arguments are left to check.
+        match T::HTML_OPEN {
+            Some(open) if token.args().is_none() => out.write_str(open).map(|_| true),
+            _ => tk_writer.write_token(token, out).map(|_| false),
         }
     }
 
-    fn close_tag<'a>(
-        &self,
-        tk_writer: &dyn crate::html::HtmlTokenWriter<CustomTy>,
-        _: &crate::Token<'a, CustomTy>,
-        close_token: &crate::Token<'a, CustomTy>,
-        out: &mut String,
-    ) {
-        if Self::TAGS.iter().any(|x| close_token.is_close_argless(x)) && Self::HTML_CLOSE.is_some()
-        {
-            out.push_str(Self::HTML_CLOSE.unwrap())
-        } else {
-            tk_writer.write_token(close_token, out)
+    fn close_tag<CustomTy>(
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &crate::Token<'_, CustomTy>,
+        close_token: &crate::Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> core::fmt::Result
+    where
+        CustomTy: Clone,
+        T: SimpleHtmlTagWriter<CustomTy>,
+    {
+        // Only close what was opened, an open tag with arguments was written out as text.
+        match T::HTML_CLOSE {
+            Some(close) if open_token.args().is_none() => out.write_str(close),
+            _ => tk_writer.write_token(close_token, out),
         }
     }
 
-    fn standalone_tag(
-        &self,
-        tk_writer: &dyn crate::html::HtmlTokenWriter<CustomTy>,
+    fn content_model<CustomTy>(token: &crate::Token<'_, CustomTy>) -> ContentModel
+    where
+        CustomTy: Clone,
+        T: SimpleHtmlTagWriter<CustomTy>,
+    {
+        match token.args() {
+            None => T::CONTENT,
+            // Written as text, so it's placed like any other text.
+            Some(_) => ContentModel::INLINE,
+        }
+    }
+
+    fn standalone_tag<CustomTy>(
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
         token: &crate::Token<'_, CustomTy>,
-        out: &mut String,
-    ) {
-        if Self::TAGS.iter().any(|x| token.is_standalone_argless(x))
-            && Self::HTML_STANDALONE.is_some()
-        {
-            out.push_str(Self::HTML_STANDALONE.unwrap())
+        out: &mut HtmlOutput<'_>,
+    ) -> core::fmt::Result
+    where
+        CustomTy: Clone,
+        T: SimpleHtmlTagWriter<CustomTy>,
+    {
+        if token.args().is_none() && T::HTML_STANDALONE.is_some() {
+            out.write_str(T::HTML_STANDALONE.unwrap())
         } else {
             tk_writer.write_token(token, out)
         }
@@ -64,10 +82,61 @@ where
     const HTML_CLOSE: Option<&'static str>;
 
     const HTML_STANDALONE: Option<&'static str>;
+
+    const CONTENT: ContentModel = ContentModel::INLINE;
+}
+
+macro_rules! simple_tag_writer {
+    ($name:ident) => {
+        impl<CustomTy> TagWriter<Html, CustomTy> for $name<CustomTy>
+        where
+            CustomTy: Clone + 'static,
+        {
+            fn match_tag(&self, tag: &str) -> bool {
+                self.match_tag_with(tag, &Default::default())
+            }
+
+            fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+                Self::TAGS.iter().any(|x| policy.names_match(x, tag))
+            }
+
+            fn open_tag(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                token: &crate::Token<'_, CustomTy>,
+                out: &mut HtmlOutput<'_>,
+            ) -> Result<bool, core::fmt::Error> {
+                Simple::<Self>::open_tag(tk_writer, token, out)
+            }
+
+            fn close_tag<'a>(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                open_token: &crate::Token<'a, CustomTy>,
+                close_token: &crate::Token<'a, CustomTy>,
+                out: &mut HtmlOutput<'_>,
+            ) -> core::fmt::Result {
+                Simple::<Self>::close_tag(tk_writer, open_token, close_token, out)
+            }
+
+            fn standalone_tag(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                token: &crate::Token<'_, CustomTy>,
+                out: &mut HtmlOutput<'_>,
+            ) -> core::fmt::Result {
+                Simple::<Self>::standalone_tag(tk_writer, token, out)
+            }
+
+            fn content_model(&self, token: &crate::Token<'_, CustomTy>) -> ContentModel {
+                Simple::<Self>::content_model(token)
+            }
+        }
+    };
 }
 
 macro_rules! simple_tag {
-    ($doc:expr, $name:ident, $tags:expr, $open:expr, $close:expr) => {
+    ($doc:expr, $name:ident, $tags:expr, $open:expr, $close:expr $(, $content:ident)?) => {
         #[derive(Copy, Clone, Debug, Default)]
         #[doc = $doc]
         #[doc = "<br/>"]
@@ -93,7 +162,10 @@ macro_rules! simple_tag {
             const HTML_CLOSE: Option<&'static str> = Some($close);
             const HTML_OPEN: Option<&'static str> = Some($open);
             const HTML_STANDALONE: Option<&'static str> = None;
+            $(const CONTENT: ContentModel = ContentModel::$content;)?
         }
+
+        simple_tag_writer!($name);
     };
 }
 
@@ -123,6 +195,8 @@ macro_rules! simple_standalone_tag {
             const HTML_OPEN: Option<&'static str> = None;
             const HTML_STANDALONE: Option<&'static str> = Some($standalone);
         }
+
+        simple_tag_writer!($name);
     };
 }
 
@@ -148,10 +222,6 @@ simple_standalone_tag! {
 LinebreakTag, ["br"], "<br/>"
 }
 simple_tag! {
-"A block quote tag with no arguments, which converts directly to HTML5 `<blockquote>`.",
-BlockQuoteTag, ["quote", "blockquote"], "<blockquote>", "</blockquote>"
-}
-simple_tag! {
 "Inline quote tag with no arguments, which converts directly to HTML5 `<q>`.",
 QuoteTag, ["q"], "<q>", "</q>"
 }
@@ -165,47 +235,43 @@ SuperscriptTag, ["sup", "super", "superscript"], "<sup>", "</sup>"
 }
 simple_tag! {
 "Header (tier 1) tag with no arguments, which converts directly to HTML5 `<h1>`.",
-Header1Tag, ["h1", "title"], "<h1>", "</h1>"
+Header1Tag, ["h1", "title"], "<h1>", "</h1>", TEXT_BLOCK
 }
 simple_tag! {
 "Header (tier 2) tag with no arguments, which converts directly to HTML5 `<h2>`.",
-Header2Tag, ["h2", "topic"], "<h2>", "</h2>"
+Header2Tag, ["h2", "topic"], "<h2>", "</h2>", TEXT_BLOCK
 }
 simple_tag! {
 "Header (tier 3) tag with no arguments, which converts directly to HTML5 `<h3>`.",
-Header3Tag, ["h3", "subtopic"], "<h3>", "</h3>"
+Header3Tag, ["h3", "subtopic"], "<h3>", "</h3>", TEXT_BLOCK
 }
 simple_tag! {
 "Header (tier 4) tag with no arguments, which converts directly to HTML5 `<h4>`.",
-Header4Tag, ["h4"], "<h4>", "</h4>"
+Header4Tag, ["h4"], "<h4>", "</h4>", TEXT_BLOCK
 }
 simple_tag! {
 "Header (tier 5) tag with no arguments, which converts directly to HTML5 `<h5>`.",
-Header5Tag, ["h5"], "<h5>", "</h5>"
+Header5Tag, ["h5"], "<h5>", "</h5>", TEXT_BLOCK
 }
 simple_tag! {
 "Header (tier 6) tag with no arguments, which converts directly to HTML5 `<h6>`.",
-Header6Tag, ["h6"], "<h6>", "</h6>"
+Header6Tag, ["h6"], "<h6>", "</h6>", TEXT_BLOCK
 }
 simple_tag! {
 "Centering tag with no arguments, which converts to a div with styling to horizontally center it.",
-CenterTag, ["center"], "<div style=\"display: flex; justify-content: center;\"><div>", "</div></div>"
+CenterTag, ["center"], "<div style=\"display: flex; justify-content: center;\"><div>", "</div></div>", BLOCK
 }
 simple_tag! {
 "Left-align tag with no arguments, which converts to a div with styling to left-align it.",
-LeftTag, ["left"], "<div style=\"display: flex; justify-content: left;\"><div>", "</div></div>"
+LeftTag, ["left"], "<div style=\"display: flex; justify-content: left;\"><div>", "</div></div>", BLOCK
 }
 simple_tag! {
 "Right-align tag with no arguments, which converts to a div with styling to right-align it.",
-RightTag, ["right"], "<div style=\"display: flex; justify-content: right;\"><div>", "</div></div>"
-}
-simple_tag! {
-"Preformatted styling tag with no arguments, which converts directly to HTML5 `<pre>`.",
-PreformattedTag, ["pre", "codeblock"], "<pre>", "</pre>"
+RightTag, ["right"], "<div style=\"display: flex; justify-content: right;\"><div>", "</div></div>", BLOCK
 }
 simple_tag! {
-"Code styling tag with no arguments, which converts directly to HTML5 `<code>`.",
-CodeTag, ["code"], "<code>", "</code>"
+"Paragraph tag with no arguments, which converts directly to HTML5 `<p>`.",
+ParagraphTag, ["p"], "<p>", "</p>", TEXT_BLOCK
 }
 simple_tag! {
 "Keypress styling tag with no arguments, which converts directly to HTML5 `<kbd>`.",
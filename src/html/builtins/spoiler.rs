@@ -0,0 +1,92 @@
+use core::fmt;
+
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    render::ContentModel,
+    Token,
+};
+
+/// Spoiler tag, which converts to HTML5 `<details>`, collapsed behind a `<summary>` with its title.
+/// <br/>
+/// This matches the following BBCode tags: `["spoiler", "hide", "collapse"]`
+/// # Arguments
+/// The title as the value, which defaults to [SpoilerTag::label].
+/// # Exact output
+/// `[spoiler=Ending]contents[/spoiler]` converts exactly to
+/// ```html
+/// <details><summary>Ending</summary>contents</details>
+/// ```
+/// # Remarks
+/// The title is escaped. Opening the spoiler needs no scripts, as browsers handle `<details>` themselves.
+#[derive(Clone, Debug)]
+pub struct SpoilerTag {
+    /// The title of spoilers not given one. Defaults to `Spoiler`.
+    pub label: String,
+}
+
+impl Default for SpoilerTag {
+    fn default() -> Self {
+        Self {
+            label: String::from("Spoiler"),
+        }
+    }
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for SpoilerTag
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        ["spoiler", "hide", "collapse"]
+            .iter()
+            .any(|x| x.eq_ignore_ascii_case(tag))
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["spoiler", "hide", "collapse"]
+            .iter()
+            .any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        let title = token
+            .arg_list()
+            .value()
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .unwrap_or(&self.label);
+
+        out.write_str("<details><summary>")?;
+        out.write_str(&html_escape::encode_safe(title))?;
+        out.write_str("</summary>")?;
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Html, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        out.write_str("</details>")
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn content_model(&self, _: &Token<'_, CustomTy>) -> ContentModel {
+        ContentModel::BLOCK
+    }
+}
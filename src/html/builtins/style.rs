@@ -0,0 +1,489 @@
+use core::fmt;
+
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    render::ContentModel,
+    Token,
+};
+
+/// How a styling tag, such as [ColorTag], writes its value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum StyleMode {
+    /// A fixed inline style, such as `<span style="color: red">`.
+    #[default]
+    Inline,
+    /// A CSS class made of the given prefix and the value, such as `<span class="bb-color-red">`, for a stylesheet to define.
+    Class(String),
+}
+
+/// The CSS named colors, in lowercase.
+const NAMED_COLORS: &[&str] = &[
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+];
+
+/// The CSS keywords for each step of the size scale, from 1 to 7.
+const SIZE_KEYWORDS: [&str; 7] = [
+    "x-small",
+    "small",
+    "medium",
+    "large",
+    "x-large",
+    "xx-large",
+    "xxx-large",
+];
+
+/// The size in pixels of each step of the size scale, from 1 to 7, at the default font size.
+const SIZE_PIXELS: [u32; 7] = [10, 13, 16, 18, 24, 32, 48];
+
+/// The generic CSS font families.
+const GENERIC_FONTS: &[&str] = &[
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+];
+
+/// Write the opening `<span>` for the given style, or class suffix if the mode uses classes.
+fn write_span(mode: &StyleMode, style: &str, class: &str, out: &mut HtmlOutput<'_>) -> fmt::Result {
+    match mode {
+        StyleMode::Inline => {
+            out.write_str("<span style=\"")?;
+            out.write_str(&html_escape::encode_double_quoted_attribute(style))?;
+        }
+        StyleMode::Class(prefix) => {
+            out.write_str("<span class=\"")?;
+            out.write_str(&html_escape::encode_double_quoted_attribute(prefix))?;
+            out.write_str(&html_escape::encode_double_quoted_attribute(class))?;
+        }
+    }
+    out.write_str("\">")
+}
+
+macro_rules! style_tag_writer {
+    ($name:ident, $tags:expr) => {
+        impl<CustomTy> TagWriter<Html, CustomTy> for $name
+        where
+            CustomTy: Clone + 'static,
+        {
+            fn match_tag(&self, tag: &str) -> bool {
+                $tags.iter().any(|x| x.eq_ignore_ascii_case(tag))
+            }
+
+            fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+                $tags.iter().any(|x| policy.names_match(x, tag))
+            }
+
+            fn open_tag(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                token: &Token<'_, CustomTy>,
+                out: &mut HtmlOutput<'_>,
+            ) -> Result<bool, fmt::Error> {
+                match token.arg_list().value().and_then(|x| self.style(x)) {
+                    Some((style, class)) => {
+                        write_span(&self.mode, &style, &class, out).map(|_| true)
+                    }
+                    None => tk_writer.write_token(token, out).map(|_| false),
+                }
+            }
+
+            fn close_tag<'a>(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                open_token: &Token<'a, CustomTy>,
+                close_token: &Token<'a, CustomTy>,
+                out: &mut HtmlOutput<'_>,
+            ) -> fmt::Result {
+                match open_token.arg_list().value().and_then(|x| self.style(x)) {
+                    Some(_) => out.write_str("</span>"),
+                    None => tk_writer.write_token(close_token, out),
+                }
+            }
+
+            fn standalone_tag(
+                &self,
+                tk_writer: &dyn TokenWriter<Html, CustomTy>,
+                token: &Token<'_, CustomTy>,
+                out: &mut HtmlOutput<'_>,
+            ) -> fmt::Result {
+                tk_writer.write_token(token, out)
+            }
+
+            fn content_model(&self, _: &crate::Token<'_, CustomTy>) -> ContentModel {
+                ContentModel::INLINE
+            }
+        }
+    };
+}
+
+/// Color tag, which converts to a `<span>` coloring its contents.
+/// <br/>
+/// This matches the following BBCode tags: `["color", "colour"]`
+/// # Arguments
+/// The color as the value, being a CSS named color, `#rgb`, `#rrggbb` or `rgb(r, g, b)`.
+/// # Exact output
+/// `[color=#F00]text[/color]` converts exactly to
+/// ```html
+/// <span style="color: #ff0000">text</span>
+/// ```
+/// With [StyleMode::Class], the class is the color name or its hex digits, such as `bb-color-ff0000`.
+/// # Remarks
+/// Any other color is written as text.
+#[derive(Clone, Debug, Default)]
+pub struct ColorTag {
+    /// How the color is written. Defaults to [StyleMode::Inline].
+    pub mode: StyleMode,
+}
+
+impl ColorTag {
+    /// Parse the given color, returning it as a lowercase name or `#rrggbb`, or `None` if it isn't allowed.
+    pub fn parse_color(value: &str) -> Option<String> {
+        let value = value.trim().to_ascii_lowercase();
+
+        if NAMED_COLORS.contains(&value.as_str()) {
+            return Some(value);
+        }
+
+        if let Some(hex) = value.strip_prefix('#') {
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+
+            return match hex.len() {
+                3 => Some(hex.chars().fold(String::from("#"), |mut acc, c| {
+                    acc.extend([c, c]);
+                    acc
+                })),
+                6 => Some(value),
+                _ => None,
+            };
+        }
+
+        let channels = value
+            .strip_prefix("rgb(")?
+            .strip_suffix(')')?
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|x| !x.is_empty())
+            .map(|x| x.parse::<u8>().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        match channels[..] {
+            [r, g, b] => Some(format!("#{r:02x}{g:02x}{b:02x}")),
+            _ => None,
+        }
+    }
+
+    /// The inline style and class suffix for the given color, if it's allowed.
+    fn style(&self, value: &str) -> Option<(String, String)> {
+        let color = Self::parse_color(value)?;
+        let class = color.trim_start_matches('#').to_owned();
+        Some((format!("color: {color}"), class))
+    }
+}
+
+style_tag_writer!(ColorTag, ["color", "colour"]);
+
+/// Size tag, which converts to a `<span>` sizing its contents on a scale from 1 to 7.
+/// <br/>
+/// This matches the following BBCode tags: `["size"]`
+/// # Arguments
+/// The size as the value, being one of:
+/// - A legacy HTML size, from `1` to `7`.
+/// - A size in pixels, such as `12` or `12px`.
+/// - A percentage of the surrounding size, such as `150%`.
+///
+/// Pixel sizes and percentages are rounded to the nearest step on the scale, then clamped to [SizeTag::min] and [SizeTag::max].
+/// # Exact output
+/// `[size=5]text[/size]` converts exactly to
+/// ```html
+/// <span style="font-size: x-large">text</span>
+/// ```
+/// With [StyleMode::Class], the class is the step on the scale, such as `bb-size-5`.
+/// # Remarks
+/// Any other size is written as text.
+#[derive(Clone, Debug)]
+pub struct SizeTag {
+    /// How the size is written. Defaults to [StyleMode::Inline].
+    pub mode: StyleMode,
+    /// The smallest step on the scale written. Defaults to 1.
+    pub min: u8,
+    /// The largest step on the scale written. Defaults to 7.
+    pub max: u8,
+}
+
+impl Default for SizeTag {
+    fn default() -> Self {
+        Self {
+            mode: StyleMode::Inline,
+            min: 1,
+            max: 7,
+        }
+    }
+}
+
+impl SizeTag {
+    /// Parse the given size, returning its step on the scale from 1 to 7, or `None` if it isn't allowed.
+    pub fn parse_size(&self, value: &str) -> Option<u8> {
+        let value = value.trim().to_ascii_lowercase();
+
+        let pixels = if let Some(percent) = value.strip_suffix('%') {
+            percent.trim().parse::<u32>().ok()?.checked_mul(16)? / 100
+        } else if let Some(pixels) = value.strip_suffix("px") {
+            pixels.trim().parse::<u32>().ok()?
+        } else {
+            match value.parse::<u32>().ok()? {
+                step @ 1..=7 => return Some((step as u8).clamp(self.min, self.max)),
+                pixels => pixels,
+            }
+        };
+
+        if pixels == 0 {
+            return None;
+        }
+
+        let step = SIZE_PIXELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, x)| x.abs_diff(pixels))
+            .map(|(idx, _)| idx as u8 + 1)?;
+        Some(step.clamp(self.min, self.max))
+    }
+
+    /// The inline style and class suffix for the given size, if it's allowed.
+    fn style(&self, value: &str) -> Option<(String, String)> {
+        let step = self.parse_size(value)?;
+        let keyword = SIZE_KEYWORDS[usize::from(step.clamp(1, 7)) - 1];
+        Some((format!("font-size: {keyword}"), step.to_string()))
+    }
+}
+
+style_tag_writer!(SizeTag, ["size"]);
+
+/// Font tag, which converts to a `<span>` setting the font of its contents, from a list of allowed fonts.
+/// <br/>
+/// This matches the following BBCode tags: `["font"]`
+/// # Arguments
+/// The font's name as the value, compared to [FontTag::fonts] ignoring ASCII case.
+/// # Exact output
+/// `[font=courier new]text[/font]` converts exactly to
+/// ```html
+/// <span style="font-family: 'Courier New'">text</span>
+/// ```
+/// With [StyleMode::Class], the class is the font's name in lowercase with spaces replaced by dashes, such as `bb-font-courier-new`.
+/// # Remarks
+/// Any other font is written as text.
+#[derive(Clone, Debug)]
+pub struct FontTag {
+    /// How the font is written. Defaults to [StyleMode::Inline].
+    pub mode: StyleMode,
+    /// The fonts which may be used, written as given here.
+    /// Defaults to a handful of fonts available almost everywhere, alongside the generic families such as `serif`.
+    pub fonts: Vec<String>,
+}
+
+impl Default for FontTag {
+    fn default() -> Self {
+        Self {
+            mode: StyleMode::Inline,
+            fonts: [
+                "Arial",
+                "Courier New",
+                "Georgia",
+                "Tahoma",
+                "Times New Roman",
+                "Trebuchet MS",
+                "Verdana",
+                "serif",
+                "sans-serif",
+                "monospace",
+                "cursive",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+impl FontTag {
+    /// Find the given font in the allowed fonts, returning `None` if it isn't allowed.
+    pub fn find_font(&self, value: &str) -> Option<&str> {
+        let value = value.trim();
+        self.fonts
+            .iter()
+            .find(|x| x.eq_ignore_ascii_case(value))
+            .map(String::as_str)
+    }
+
+    /// The inline style and class suffix for the given font, if it's allowed.
+    fn style(&self, value: &str) -> Option<(String, String)> {
+        let font = self.find_font(value)?;
+        let class = font
+            .chars()
+            .filter_map(|c| match c {
+                ' ' => Some('-'),
+                c if c.is_ascii_alphanumeric() || c == '-' => Some(c.to_ascii_lowercase()),
+                _ => None,
+            })
+            .collect();
+
+        // Generic families are keywords, so can't be quoted.
+        let style = if GENERIC_FONTS.iter().any(|x| x.eq_ignore_ascii_case(font)) {
+            format!("font-family: {font}")
+        } else {
+            format!("font-family: '{}'", font.replace(['\'', '\\'], ""))
+        };
+
+        Some((style, class))
+    }
+}
+
+style_tag_writer!(FontTag, ["font"]);
@@ -0,0 +1,265 @@
+use core::{fmt, num::IntErrorKind};
+
+use crate::{
+    html::{Html, HtmlOutput, TagWriter, TokenWriter},
+    render::ContentModel,
+    Token,
+};
+
+/// Table tag with no arguments, which converts directly to HTML5 `<table>`.
+/// <br/>
+/// This matches the following BBCode tags: `["table"]`
+/// # Exact output
+/// `[table][tr][th]Name[/th][/tr][tr][td]Value[/td][/tr][/table]` converts exactly to
+/// ```html
+/// <table><tr><th>Name</th></tr><tr><td>Value</td></tr></table>
+/// ```
+/// # Remarks
+/// Rows ([TableRowTag]) are only written directly within a table, and cells ([TableCellTag]) directly within a row,
+/// otherwise they're written as text.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TableTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for TableTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        policy.names_match("table", tag)
+    }
+
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        match token.args() {
+            None => out.write_str("<table>").map(|_| true),
+            Some(_) => tk_writer.write_token(token, out).map(|_| false),
+        }
+    }
+
+    fn close_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        close_token: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        match open_token.args() {
+            None => out.write_str("</table>"),
+            Some(_) => tk_writer.write_token(close_token, out),
+        }
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn content_model(&self, token: &Token<'_, CustomTy>) -> ContentModel {
+        match token.args() {
+            None => ContentModel::BLOCK.with_role("table"),
+            // Written as text, so it can't hold rows.
+            Some(_) => ContentModel::INLINE,
+        }
+    }
+}
+
+/// Table row tag with no arguments, which converts directly to HTML5 `<tr>` within a [TableTag].
+/// Outside of a table it is written as text.
+/// <br/>
+/// This matches the following BBCode tags: `["tr"]`
+/// # Exact output
+/// This tag converts exactly to
+/// ```html
+/// <tr>contents</tr>
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TableRowTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for TableRowTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        policy.names_match("tr", tag)
+    }
+
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        match token.args() {
+            None => out.write_str("<tr>").map(|_| true),
+            Some(_) => tk_writer.write_token(token, out).map(|_| false),
+        }
+    }
+
+    fn close_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        close_token: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        match open_token.args() {
+            None => out.write_str("</tr>"),
+            Some(_) => tk_writer.write_token(close_token, out),
+        }
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn content_model(&self, token: &Token<'_, CustomTy>) -> ContentModel {
+        match token.args() {
+            None => ContentModel::BLOCK.with_role("row").within("table"),
+            Some(_) => ContentModel::INLINE,
+        }
+    }
+}
+
+/// Table cell tag, which converts to HTML5 `<td>`, or `<th>` for header cells, within a [TableRowTag].
+/// Outside of a row it is written as text.
+/// <br/>
+/// This matches the following BBCode tags: `["td", "th"]`
+/// # Arguments
+/// - `colspan`, the number of columns the cell spans.
+/// - `rowspan`, the number of rows the cell spans.
+/// # Exact output
+/// `[td colspan=2]cell[/td]` converts exactly to
+/// ```html
+/// <td colspan="2">cell</td>
+/// ```
+/// # Remarks
+/// Spans are clamped to between 1 and the maximums, and any which aren't whole numbers are left out.
+#[derive(Clone, Debug)]
+pub struct TableCellTag {
+    /// The most columns a cell may span. Defaults to 100.
+    pub max_colspan: u32,
+    /// The most rows a cell may span. Defaults to 100.
+    pub max_rowspan: u32,
+}
+
+impl Default for TableCellTag {
+    fn default() -> Self {
+        Self {
+            max_colspan: 100,
+            max_rowspan: 100,
+        }
+    }
+}
+
+impl TableCellTag {
+    /// The column and row spans given to the cell, clamped to the maximums. Spans of 1 are left out, as they're the default.
+    pub fn spans<CustomTy: Clone>(
+        &self,
+        token: &Token<'_, CustomTy>,
+    ) -> (Option<u32>, Option<u32>) {
+        let args = token.arg_list();
+        let parse = |name: &str, max: u32| {
+            let span = match args.get(name)?.trim().parse::<u32>() {
+                Ok(x) => x,
+                // Too large to parse, but still a whole number, so it's clamped like any other.
+                Err(e) if *e.kind() == IntErrorKind::PosOverflow => u32::MAX,
+                Err(_) => return None,
+            };
+            Some(span.clamp(1, max.max(1))).filter(|x| *x > 1)
+        };
+
+        (
+            parse("colspan", self.max_colspan),
+            parse("rowspan", self.max_rowspan),
+        )
+    }
+}
+
+impl<CustomTy> TagWriter<Html, CustomTy> for TableCellTag
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        ["td", "th"].iter().any(|x| x.eq_ignore_ascii_case(tag))
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["td", "th"].iter().any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        let name = if token.matches_tag("th", &Default::default()) {
+            "th"
+        } else {
+            "td"
+        };
+        let (colspan, rowspan) = self.spans(token);
+
+        write!(out, "<{name}")?;
+        if let Some(colspan) = colspan {
+            write!(out, " colspan=\"{colspan}\"")?;
+        }
+        if let Some(rowspan) = rowspan {
+            write!(out, " rowspan=\"{rowspan}\"")?;
+        }
+        out.write_str(">")?;
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        if open_token.matches_tag("th", &Default::default()) {
+            out.write_str("</th>")
+        } else {
+            out.write_str("</td>")
+        }
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn content_model(&self, _: &Token<'_, CustomTy>) -> ContentModel {
+        ContentModel::BLOCK.within("row")
+    }
+}
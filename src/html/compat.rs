@@ -0,0 +1,209 @@
+//! The HTML writer traits from before writers were shared between backends, kept so existing writers still work.
+#![allow(deprecated)]
+
+use core::fmt;
+
+use crate::{rules::ParserRuleObjBox, TagNamePolicy, Token};
+
+use super::{Html, HtmlOutput, TagWriter, TokenWriter};
+
+/// The primary trait for converting BBCode tags to HTML, pushing into a [String].
+/// # Remarks
+/// Implementations can still be registered with an [HtmlSerializer][super::HtmlSerializer] by wrapping them in [Legacy].
+/// They can't describe their [content model][TagWriter::content_model] or [take their contents][TagWriter::takes_contents],
+/// and get the defaults.
+#[deprecated(note = "implement `TagWriter<Html, CustomTy>` instead")]
+pub trait HtmlTagWriter<CustomTy = ()>
+where
+    CustomTy: Clone + 'static,
+{
+    /// Whether or not this generator is the correct generator for the given tag.
+    /// # Remarks
+    /// The implementation reserves the right to cache the result of this function on a per-instance basis, this function MUST
+    /// always return the same output for each possible input.
+    fn match_tag(&self, tag: &str) -> bool;
+
+    /// Whether or not this generator is the correct generator for the given tag, comparing names with the given policy.
+    /// # Remarks
+    /// The default implementation ignores the policy and defers to [HtmlTagWriter::match_tag].
+    fn match_tag_with(&self, tag: &str, _policy: &TagNamePolicy) -> bool {
+        self.match_tag(tag)
+    }
+
+    /// Produce an open tag for the given token, pushing it into the given buffer.
+    /// # Remarks
+    /// The `out` buffer provided may already have contents, an implementation must not overwrite prior contents.
+    fn open_tag(
+        &self,
+        tk_writer: &dyn HtmlTokenWriter<CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut String,
+    );
+
+    /// Produce a close tag for the given token, pushing it into the given buffer.
+    /// # Remarks
+    /// The `out` buffer provided may already have contents, an implementation must not overwrite prior contents.
+    fn close_tag<'a>(
+        &self,
+        tk_writer: &dyn HtmlTokenWriter<CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        close_token: &Token<'a, CustomTy>,
+        out: &mut String,
+    );
+
+    /// Produce a standalone tag for the given token, pushing it into the given buffer.
+    /// # Remarks
+    /// The `out` buffer provided may already have contents, an implementation must not overwrite prior contents.
+    /// Standalone tags MUST NOT rely on other tags around them.
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn HtmlTokenWriter<CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut String,
+    );
+
+    /// Try to produce a new parser rule to introduce from the given token.
+    fn try_special<'a>(
+        &self,
+        _token: &'_ Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        None
+    }
+}
+
+/// Implements writing non-tag tokens, pushing into a [String].
+/// # Remarks
+/// Implementations can still be used by an [HtmlSerializer][super::HtmlSerializer] by wrapping them in [Legacy].
+#[deprecated(note = "implement `TokenWriter<Html, CustomTy>` instead")]
+pub trait HtmlTokenWriter<CustomTy = ()>
+where
+    CustomTy: Clone,
+{
+    /// Write HTML for the given token, pushing it into the given buffer.
+    /// # Remarks
+    /// The token is not guaranteed to only be text or custom tags, if a tag fails to match any tag writer it will end up here.
+    /// The `out` buffer provided may already have contents, an implementation must not overwrite prior contents.
+    fn write_token(&self, token: &Token<'_, CustomTy>, out: &mut String);
+}
+
+/// Passes the token writer given to a [TagWriter] on to an [HtmlTagWriter].
+struct Tokens<'w, CustomTy>(&'w dyn TokenWriter<Html, CustomTy>);
+
+impl<'w, CustomTy> HtmlTokenWriter<CustomTy> for Tokens<'w, CustomTy>
+where
+    CustomTy: Clone,
+{
+    fn write_token(&self, token: &Token<'_, CustomTy>, out: &mut String) {
+        // Writing to a String never fails, and there's no way to report the writer's own errors.
+        let _ = self.0.write_token(token, out);
+    }
+}
+
+/// Adapts an [HtmlTagWriter] or [HtmlTokenWriter] into a [TagWriter] or [TokenWriter] for [Html].
+/// # Example
+/// ```
+/// # #![allow(deprecated)]
+/// use bbx::{html::{HtmlSerializer, HtmlTagWriter, HtmlTokenWriter, Legacy, SimpleHtmlWriter}, BBParser, Token};
+///
+/// type Tk<'a> = Token<'a, ()>;
+///
+/// struct MarkTag;
+///
+/// impl HtmlTagWriter for MarkTag {
+///     fn match_tag(&self, tag: &str) -> bool {
+///         tag == "mark"
+///     }
+///
+///     fn open_tag(&self, _: &dyn HtmlTokenWriter, _: &Tk<'_>, out: &mut String) {
+///         out.push_str("<mark>");
+///     }
+///
+///     fn close_tag(&self, _: &dyn HtmlTokenWriter, _: &Tk<'_>, _: &Tk<'_>, out: &mut String) {
+///         out.push_str("</mark>");
+///     }
+///
+///     fn standalone_tag(&self, tk_writer: &dyn HtmlTokenWriter, token: &Tk<'_>, out: &mut String) {
+///         tk_writer.write_token(token, out);
+///     }
+/// }
+///
+/// let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::empty();
+/// serializer.register_tag(Box::new(Legacy(MarkTag)));
+/// assert_eq!(serializer.serialize(BBParser::new("[mark]x[/mark]")), "<mark>x</mark>");
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Legacy<T>(pub T);
+
+/// Run the given write into a buffer, then copy it to the output.
+fn buffered(out: &mut HtmlOutput<'_>, write: impl FnOnce(&mut String)) -> fmt::Result {
+    let mut buf = String::new();
+    write(&mut buf);
+    out.write_str(&buf)
+}
+
+impl<T, CustomTy> TagWriter<Html, CustomTy> for Legacy<T>
+where
+    T: HtmlTagWriter<CustomTy>,
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        HtmlTagWriter::match_tag(&self.0, tag)
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &TagNamePolicy) -> bool {
+        HtmlTagWriter::match_tag_with(&self.0, tag, policy)
+    }
+
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> Result<bool, fmt::Error> {
+        // There's no way to tell whether the tag was written out as text, so it's always assumed to be opened.
+        buffered(out, |buf| {
+            HtmlTagWriter::open_tag(&self.0, &Tokens(tk_writer), token, buf)
+        })?;
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        close_token: &Token<'a, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        buffered(out, |buf| {
+            HtmlTagWriter::close_tag(&self.0, &Tokens(tk_writer), open_token, close_token, buf)
+        })
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Html, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut HtmlOutput<'_>,
+    ) -> fmt::Result {
+        buffered(out, |buf| {
+            HtmlTagWriter::standalone_tag(&self.0, &Tokens(tk_writer), token, buf)
+        })
+    }
+
+    fn try_special<'a>(
+        &self,
+        token: &'_ Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        HtmlTagWriter::try_special(&self.0, token)
+    }
+}
+
+impl<T, CustomTy> TokenWriter<Html, CustomTy> for Legacy<T>
+where
+    T: HtmlTokenWriter<CustomTy>,
+    CustomTy: Clone,
+{
+    fn write_token(&self, token: &Token<'_, CustomTy>, out: &mut HtmlOutput<'_>) -> fmt::Result {
+        buffered(out, |buf| HtmlTokenWriter::write_token(&self.0, token, buf))
+    }
+}
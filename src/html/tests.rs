@@ -34,3 +34,831 @@ pub fn dangling() {
 
     assert_eq!(serializer.serialize(parser), "<h1>This is a test!</h1>");
 }
+
+const CASE_SENSITIVE: &str = "[B]x[/B] [b]y[/b]";
+
+#[test]
+pub fn case_sensitive() {
+    let parser = BBParser::with_config(
+        CASE_SENSITIVE,
+        crate::ParserConfig {
+            tag_names: crate::TagNamePolicy {
+                case_sensitive: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let mut serializer =
+        HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_core_v1_tags());
+
+    assert_eq!(serializer.serialize(parser), "[B]x[&#x2F;B] <b>y</b>");
+}
+
+const STREAMED: &str = "[b]Streamed[/b] <output>";
+
+#[test]
+pub fn streamed_fmt() {
+    let mut serializer =
+        HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_core_v1_tags());
+    let mut out = String::from("<p>");
+
+    serializer
+        .render(BBParser::new(STREAMED), &mut out)
+        .unwrap();
+
+    assert_eq!(out, "<p><b>Streamed</b> &lt;output&gt;");
+}
+
+#[test]
+pub fn streamed_io() {
+    let mut serializer =
+        HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_core_v1_tags());
+    let mut out: Vec<u8> = Vec::new();
+
+    serializer
+        .serialize_io(BBParser::new(STREAMED), &mut out)
+        .unwrap();
+
+    assert_eq!(out, b"<b>Streamed</b> &lt;output&gt;");
+}
+
+/// A sink that fails after accepting the given number of bytes.
+#[derive(Debug)]
+struct FailingSink(usize);
+
+impl std::io::Write for FailingSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.len() > self.0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "full"));
+        }
+        self.0 -= buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+pub fn streamed_io_error() {
+    let mut serializer =
+        HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_core_v1_tags());
+
+    let err = serializer
+        .serialize_io(BBParser::new(STREAMED), FailingSink(5))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+}
+
+const MISNESTED: &str = "[b][i]x[/b]y[/i] [b][i][u]x[/b]y[/u]z[/i]";
+
+#[test]
+pub fn misnested() {
+    let parser = BBParser::with_config(
+        MISNESTED,
+        crate::ParserConfig {
+            feature_flags: crate::ParserFeature::V1,
+            ..Default::default()
+        },
+    );
+    let mut serializer =
+        HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_core_v1_tags());
+
+    assert_eq!(
+        serializer.serialize(parser),
+        "<b><i>x</i></b><i>y</i> <b><i><u>x</u></i></b><i><u>y</u>z</i>"
+    );
+}
+
+const DANGLING_NESTED: &str = "[b][i]text";
+
+#[test]
+pub fn dangling_nested() {
+    let parser = BBParser::new(DANGLING_NESTED);
+    let mut serializer =
+        HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_core_v1_tags());
+
+    assert_eq!(serializer.serialize(parser), "<b><i>text</i></b>");
+}
+
+/// Elements which never have a close tag.
+const VOID_ELEMENTS: &[&str] = &["br", "hr", "img"];
+
+/// Check that every element opened in the given HTML is closed exactly once, in reverse order of opening.
+/// # Panics
+/// If the HTML is not well formed.
+fn assert_well_formed(html: &str) {
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = html;
+
+    while let Some(idx) = rest.find('<') {
+        let end = rest[idx..]
+            .find('>')
+            .unwrap_or_else(|| panic!("unterminated tag in {html:?}"))
+            + idx;
+        let tag = &rest[(idx + 1)..end];
+        rest = &rest[(end + 1)..];
+
+        let (is_close, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let self_closing = tag.ends_with('/');
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap();
+        assert!(
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric()),
+            "bad tag name {name:?} in {html:?}"
+        );
+
+        if is_close {
+            assert_eq!(open.pop(), Some(name), "close tag out of order in {html:?}");
+        } else if !self_closing && !VOID_ELEMENTS.contains(&name) {
+            open.push(name);
+        }
+    }
+
+    assert!(open.is_empty(), "unclosed {open:?} in {html:?}");
+}
+
+const WELL_FORMED: &[&str] = &[
+    "[b][i]text",
+    "[b][i]x[/b]y[/i]",
+    "[center][b]x[/center]y[/b]",
+    "[quote][left][right]x[/quote]",
+    "[b][i][u][s]x[/b][/u]y",
+    "[h1][sub][sup]x[/h1]y[/sub]z",
+    "[pre][code]x[/pre][kbd]y",
+    "[b]x[br][i]y[/b][/i]",
+    "[b][unknown]x[/b][/unknown]",
+    "[/b][/i]x[b]",
+    "[b]x[quote][i]y[/b]z[/quote]",
+    "[h1][b]x[h2][i]y[/b][/h2]",
+    "[url=https://example.com]a[b]x[/url]c",
+    "[b][url]https://example.com[/b]",
+    "[list][*]x[*][b]y[/list]z[/b]",
+    "[b][list][li]x[/b][/li][*]y",
+    "[*]x[list=a][quote][*]y[/quote][/list][*]",
+    "[table][tr][td]x[tr][td]y[/table]",
+    "[b][table][tr][th][i]x[/b][/th][td]y[/i][/tr][/table]",
+    "[quote=\"<b>\" post=1][b]x[/quote]y",
+    "[b]x[spoiler][quote]y[/b][/spoiler]z",
+    "[b][pre=rust]x[/b]y[i][code]z",
+    "[b=x][/b]",
+    "[h1=x]t[/h1]",
+    "[b][center=x]y[/b]z[/center]",
+    "[quote][h2=x]y[/quote][/h2]",
+];
+
+#[test]
+pub fn well_formed() {
+    for feature_flags in [crate::ParserFeature::NONE, crate::ParserFeature::V1] {
+        for input in WELL_FORMED {
+            let mut serializer =
+                HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+            let html = serializer.serialize(BBParser::with_config(
+                input,
+                crate::ParserConfig {
+                    feature_flags,
+                    ..Default::default()
+                },
+            ));
+
+            assert_well_formed(&html);
+        }
+    }
+}
+
+const BLOCK_IN_INLINE: &str = "[b]x[quote]y[/quote]z[/b] [i][u]x[center]y[/center]z[/u][/i]";
+
+#[test]
+pub fn block_in_inline() {
+    let parser = BBParser::new(BLOCK_IN_INLINE);
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+
+    assert_eq!(
+        serializer.serialize(parser),
+        concat!(
+            "<b>x</b><blockquote>y</blockquote><b>z</b> ",
+            "<i><u>x</u></i><div style=\"display: flex; justify-content: center;\"><div>y</div></div><i><u>z</u></i>"
+        )
+    );
+}
+
+const EMPTY_AROUND_BLOCK: &str =
+    "[b][quote]x[/quote][/b] [b][i][center]y[/center]z[/i][/b] [u][/u]";
+
+#[test]
+pub fn empty_around_block() {
+    // Inline tags with nothing in them before a block aren't written there, but empty ones without a block still are.
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+    assert_eq!(
+        serializer.serialize(BBParser::new(EMPTY_AROUND_BLOCK)),
+        concat!(
+            "<blockquote>x</blockquote> ",
+            "<div style=\"display: flex; justify-content: center;\"><div>y</div></div><b><i>z</i></b> <u></u>"
+        )
+    );
+    assert_eq!(
+        serialize_with_newlines("[b][quote]x[/quote][/b]\n[i]a\n[b]b[/b][/i]"),
+        "<blockquote><p>x</p></blockquote><p><i>a<br/><b>b</b></i></p>"
+    );
+}
+
+const BLOCK_IN_TEXT_BLOCK: &str = "[h1]x[h2]y[/h2]z[/h1] [h1][b]x[quote]y[/quote][/b][/h1]";
+
+#[test]
+pub fn block_in_text_block() {
+    let parser = BBParser::new(BLOCK_IN_TEXT_BLOCK);
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+
+    assert_eq!(
+        serializer.serialize(parser),
+        "<h1>x[h2]y[&#x2F;h2]z</h1> <h1><b>x[quote]y[&#x2F;quote]</b></h1>"
+    );
+}
+
+fn serialize_with_newlines(input: &str) -> String {
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+    serializer.set_newlines(Some(builtins::newlines()));
+    serializer.serialize(BBParser::new(input))
+}
+
+const PARAGRAPHS: &str = "a\nb\r\n\nc [b]d\n\n\ne[/b]\n";
+
+#[test]
+pub fn paragraphs() {
+    assert_eq!(
+        serialize_with_newlines(PARAGRAPHS),
+        "<p>a<br/>b</p><p>c <b>d</b></p><p><b>e</b></p>"
+    );
+}
+
+const NEWLINES_AROUND_BLOCKS: &str =
+    "[h1]Title[/h1]\nText\n[quote]\nQuoted\n\nMore\n[/quote]\n\n[b]x[center]y[/center]\nz[/b]";
+
+#[test]
+pub fn newlines_around_blocks() {
+    assert_eq!(
+        serialize_with_newlines(NEWLINES_AROUND_BLOCKS),
+        concat!(
+            "<h1>Title</h1><p>Text</p><blockquote><p>Quoted</p><p>More</p></blockquote>",
+            "<p><b>x</b></p><div style=\"display: flex; justify-content: center;\"><div><p>y</p></div></div>",
+            "<p><b>z</b></p>"
+        )
+    );
+}
+
+const NEWLINES_VERBATIM: &str = "[h1]a\nb[/h1][pre]c\n\nd[/pre]e [code]f\ng[/code]";
+
+#[test]
+pub fn newlines_verbatim() {
+    assert_eq!(
+        serialize_with_newlines(NEWLINES_VERBATIM),
+        "<h1>a<br/>b</h1><pre>c\n\nd</pre><p>e <code>f\ng</code></p>"
+    );
+}
+
+#[test]
+pub fn well_formed_newlines() {
+    for input in WELL_FORMED {
+        assert_well_formed(&serialize_with_newlines(&input.replace('x', "x\n\n")));
+    }
+}
+
+fn serialize_all(input: &str) -> String {
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+    serializer.serialize(BBParser::new(input))
+}
+
+const LINKS: &str = "[url]https://example.com/?a=1&b=2[/url] [url=\"mailto:me@example.com\"][b]Mail[/b] me[/url] [url=/relative]x[/url]";
+
+#[test]
+pub fn links() {
+    assert_eq!(
+        serialize_all(LINKS),
+        concat!(
+            "<a href=\"https://example.com/?a=1&amp;b=2\" rel=\"nofollow ugc noopener\">",
+            "https:&#x2F;&#x2F;example.com&#x2F;?a=1&amp;b=2</a> ",
+            "<a href=\"mailto:me@example.com\" rel=\"nofollow ugc noopener\"><b>Mail</b> me</a> ",
+            "<a href=\"/relative\" rel=\"nofollow ugc noopener\">x</a>"
+        )
+    );
+}
+
+const UNSAFE_LINKS: &str = "[url]java\nscript:alert(1)[/url] [url=JavaScript:alert(1)][i]x[/i][/url] [url=ftp://example.com]y[/url] [url]\"><script>[/url]";
+
+#[test]
+pub fn unsafe_links() {
+    assert_eq!(
+        serialize_all(UNSAFE_LINKS),
+        concat!(
+            "[url]java\nscript:alert(1)[&#x2F;url] ",
+            "[url=JavaScript:alert(1)]<i>x</i>[&#x2F;url] ",
+            "[url=ftp:&#x2F;&#x2F;example.com]y[&#x2F;url] ",
+            "<a href=\"&quot;&gt;&lt;script&gt;\" rel=\"nofollow ugc noopener\">&quot;&gt;&lt;script&gt;</a>"
+        )
+    );
+}
+
+const LINK_TEXT: &str = "[url]not a url at all[/url] [url]https://a.example/[b]x[/b][/url] [url]https://b.example [b]y[/b]";
+
+#[test]
+pub fn link_text() {
+    // Only a single URL is linked, and a link that's never closed doesn't take the rest of the document.
+    assert_eq!(
+        serialize_all(LINK_TEXT),
+        concat!(
+            "[url]not a url at all[&#x2F;url] ",
+            "[url]https:&#x2F;&#x2F;a.example&#x2F;[b]x[&#x2F;b][&#x2F;url] ",
+            "[url]https:&#x2F;&#x2F;b.example <b>y</b>"
+        )
+    );
+}
+
+const LINK_POLICY: &str = "[url=ftp://example.com]x[/url] [url]https://example.com[/url]";
+
+#[test]
+pub fn link_policy() {
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::empty();
+    serializer.register_tag(Box::new(builtins::LinkTag {
+        schemes: vec![String::from("ftp")],
+        rel: None,
+        target: Some(String::from("_blank")),
+    }));
+
+    assert_eq!(
+        serializer.serialize(BBParser::new(LINK_POLICY)),
+        "<a href=\"ftp://example.com\" target=\"_blank\">x</a> [url]https:&#x2F;&#x2F;example.com[&#x2F;url]"
+    );
+}
+
+const IMAGES: &str = "[img]https://example.com/a.png[/img] [img=100x5000 alt='A \"cat\"']/b.png[/img] [img width=20]c.png[/img]";
+
+#[test]
+pub fn images() {
+    assert_eq!(
+        serialize_all(IMAGES),
+        concat!(
+            "<img src=\"https://example.com/a.png\" alt=\"Image\" loading=\"lazy\"/> ",
+            "<img src=\"/b.png\" alt=\"A &quot;cat&quot;\" width=\"100\" height=\"2048\" loading=\"lazy\"/> ",
+            "<img src=\"c.png\" alt=\"Image\" width=\"20\" loading=\"lazy\"/>"
+        )
+    );
+}
+
+const UNSAFE_IMAGES: &str = "[img]javascript:alert(1)[/img] [img=0x10]a.png[/img] [img=big]a.png[/img] [img]mailto:me@example.com";
+
+#[test]
+pub fn unsafe_images() {
+    assert_eq!(
+        serialize_all(UNSAFE_IMAGES),
+        concat!(
+            "[img]javascript:alert(1)[&#x2F;img] ",
+            "[img=0x10]a.png[&#x2F;img] ",
+            "[img=big]a.png[&#x2F;img] ",
+            "[img]mailto:me@example.com"
+        )
+    );
+}
+
+const IMAGE_TEXT: &str = "[img]https://x.example/a b.png[/img] [img]\thttps://x.example/b.png\n[/img] [img]https://x.example/c.png [b]z[/b]";
+
+#[test]
+pub fn image_text() {
+    assert_eq!(
+        serialize_all(IMAGE_TEXT),
+        concat!(
+            "[img]https:&#x2F;&#x2F;x.example&#x2F;a b.png[&#x2F;img] ",
+            "<img src=\"https://x.example/b.png\" alt=\"Image\" loading=\"lazy\"/> ",
+            "[img]https:&#x2F;&#x2F;x.example&#x2F;c.png <b>z</b>"
+        )
+    );
+}
+
+const IMAGE_PROXY: &str = "[img]https://example.com/a.png[/img][img]http://localhost/x.png[/img]";
+
+#[test]
+pub fn image_proxy() {
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::empty();
+    serializer.register_tag(Box::new(builtins::ImageTag {
+        rewrite_url: Some(Box::new(|url| {
+            (!url.contains("localhost")).then(|| format!("/proxy?url={url}"))
+        })),
+        ..Default::default()
+    }));
+
+    assert_eq!(
+        serializer.serialize(BBParser::new(IMAGE_PROXY)),
+        concat!(
+            "<img src=\"/proxy?url=https://example.com/a.png\" alt=\"Image\" loading=\"lazy\"/>",
+            "[img]http:&#x2F;&#x2F;localhost&#x2F;x.png[&#x2F;img]"
+        )
+    );
+}
+
+const STYLES: &str = "[color=Red]a[/color][color=#0F0]b[/color][color=rgb(0, 0, 255)]c[/color][size=5]d[/size][size=150%]e[/size][size=100px]f[/size][font=courier new]g[/font][font=MONOSPACE]h[/font]";
+
+#[test]
+pub fn styles() {
+    assert_eq!(
+        serialize_all(STYLES),
+        concat!(
+            "<span style=\"color: red\">a</span>",
+            "<span style=\"color: #00ff00\">b</span>",
+            "<span style=\"color: #0000ff\">c</span>",
+            "<span style=\"font-size: x-large\">d</span>",
+            "<span style=\"font-size: x-large\">e</span>",
+            "<span style=\"font-size: xxx-large\">f</span>",
+            "<span style=\"font-family: 'Courier New'\">g</span>",
+            "<span style=\"font-family: monospace\">h</span>"
+        )
+    );
+}
+
+const UNSAFE_STYLES: &str = "[color=red;background:url(x)]a[/color][color=#12345]b[/color][color=rgb(300,0,0)]c[/color][size=0]d[/size][size=big]e[/size][font=Comic Sans MS]f[/font][font=\"x'};\"]g[/font]";
+
+#[test]
+pub fn unsafe_styles() {
+    assert_eq!(
+        serialize_all(UNSAFE_STYLES),
+        concat!(
+            "[color=red;background:url(x)]a[&#x2F;color]",
+            "[color=#12345]b[&#x2F;color]",
+            "[color=rgb(300,0,0)]c[&#x2F;color]",
+            "[size=0]d[&#x2F;size]",
+            "[size=big]e[&#x2F;size]",
+            "[font=Comic Sans MS]f[&#x2F;font]",
+            "[font=&quot;x&#x27;};&quot;]g[&#x2F;font]"
+        )
+    );
+}
+
+const STYLE_CLASSES: &str =
+    "[color=#ABC]a[/color][size=1]b[/size][size=7]c[/size][font=times new roman]d[/font]";
+
+#[test]
+pub fn style_classes() {
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::empty();
+    serializer.register_tag(Box::new(builtins::ColorTag {
+        mode: builtins::StyleMode::Class(String::from("bb-color-")),
+    }));
+    serializer.register_tag(Box::new(builtins::SizeTag {
+        mode: builtins::StyleMode::Class(String::from("bb-size-")),
+        min: 2,
+        max: 6,
+    }));
+    serializer.register_tag(Box::new(builtins::FontTag {
+        mode: builtins::StyleMode::Class(String::from("bb-font-")),
+        ..Default::default()
+    }));
+
+    assert_eq!(
+        serializer.serialize(BBParser::new(STYLE_CLASSES)),
+        concat!(
+            "<span class=\"bb-color-aabbcc\">a</span>",
+            "<span class=\"bb-size-2\">b</span>",
+            "<span class=\"bb-size-6\">c</span>",
+            "<span class=\"bb-font-times-new-roman\">d</span>"
+        )
+    );
+}
+
+fn serialize_lists(input: &str) -> String {
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+    serializer.serialize(BBParser::with_config(
+        input,
+        crate::ParserConfig {
+            void_tags: crate::ParserConfig::COMMON_VOID_TAGS,
+            ..Default::default()
+        },
+    ))
+}
+
+const LISTS: &str = "[list][*]a[*][b]b[/b][/list][ol][li]c[/li][li]d[/li][/ol][list=1][*]e[list][*]f[/list][*]g[/list]";
+
+#[test]
+pub fn lists() {
+    assert_eq!(
+        serialize_lists(LISTS),
+        concat!(
+            "<ul><li>a</li><li><b>b</b></li></ul>",
+            "<ol><li>c</li><li>d</li></ol>",
+            "<ol><li>e<ul><li>f</li></ul></li><li>g</li></ol>"
+        )
+    );
+}
+
+const ORDERED_LISTS: &str = "[list=a][*]a[/list][list=A][*]b[/list][list=i][*]c[/list][list=I][*]d[/list][list=3][*]e[/list][list=disc][*]f[/list]";
+
+#[test]
+pub fn ordered_lists() {
+    assert_eq!(
+        serialize_lists(ORDERED_LISTS),
+        concat!(
+            "<ol type=\"a\"><li>a</li></ol><ol type=\"A\"><li>b</li></ol>",
+            "<ol type=\"i\"><li>c</li></ol><ol type=\"I\"><li>d</li></ol>",
+            "<ol start=\"3\"><li>e</li></ol>[list=disc][*]f[&#x2F;list]"
+        )
+    );
+}
+
+const LIST_ITEMS_OUTSIDE: &str = "[*]a [li]b[/li] [list][quote][*]c[/quote][/list]";
+
+#[test]
+pub fn list_items_outside() {
+    assert_eq!(
+        serialize_lists(LIST_ITEMS_OUTSIDE),
+        "[*]a [li]b[&#x2F;li] <ul><li>[quote]c[&#x2F;quote]</li></ul>"
+    );
+}
+
+const LIST_CONTENTS: &str =
+    "[list]intro [*]a[/list] [list][b][*]b[/b] [*]c[/list] [list] [*]d [/list] [list]e[/list]";
+
+#[test]
+pub fn list_contents() {
+    // Lists only hold items, so anything else is moved into the next one, or after the list.
+    assert_eq!(
+        serialize_lists(LIST_CONTENTS),
+        "<ul><li>intro a</li></ul> <ul><li><b>b</b> </li><li>c</li></ul> <ul><li>d </li></ul> <ul></ul>e"
+    );
+}
+
+const LIST_ITEMS_MISNESTED: &str = "[list][*][b]a[*]b[/b][*]c[/list]";
+
+#[test]
+pub fn list_items_misnested() {
+    assert_eq!(
+        serialize_lists(LIST_ITEMS_MISNESTED),
+        "<ul><li><b>a</b></li><li><b>b</b></li><li>c</li></ul>"
+    );
+}
+
+#[test]
+pub fn well_formed_lists() {
+    for input in WELL_FORMED {
+        assert_well_formed(&serialize_lists(input));
+    }
+}
+
+const TABLES: &str = "[table][tr][th]Name[/th][th colspan=2]Value[/th][/tr][tr][td rowspan=\"3\"][b]a[/b][/td][td colspan=1]b[/td][td colspan=x]c[/td][/tr][/table]";
+
+#[test]
+pub fn tables() {
+    assert_eq!(
+        serialize_all(TABLES),
+        concat!(
+            "<table><tr><th>Name</th><th colspan=\"2\">Value</th></tr>",
+            "<tr><td rowspan=\"3\"><b>a</b></td><td>b</td><td>c</td></tr></table>"
+        )
+    );
+}
+
+const MISPLACED_TABLES: &str =
+    "[td]a[/td][tr]b[/tr][table][td]c[/td][tr][quote][td]d[/td][/quote][/tr][/table]";
+
+#[test]
+pub fn misplaced_tables() {
+    assert_eq!(
+        serialize_all(MISPLACED_TABLES),
+        concat!(
+            "[td]a[&#x2F;td][tr]b[&#x2F;tr]<table><tr><td>[td]c[&#x2F;td][quote]d</td></tr></table>",
+            "[&#x2F;quote]"
+        )
+    );
+}
+
+const TABLE_CONTENTS: &str = "[table]x[tr]y[td]a[/td]z[/tr][/table] [table] [tr] [td]b[/td] [/tr] [/table] [table][quote]c[/quote][table][tr][td]d[/td][/tr][/table][/table]";
+
+#[test]
+pub fn table_contents() {
+    // Tables and rows only hold their own children, so anything else is moved into the next cell, or after the table.
+    assert_eq!(
+        serialize_all(TABLE_CONTENTS),
+        concat!(
+            "<table><tr><td>xya</td></tr></table>z <table><tr><td>b</td></tr></table> ",
+            "<table><tr><td>[quote]c[&#x2F;quote][table]d</td></tr></table>[&#x2F;table]"
+        )
+    );
+}
+
+const TABLE_SPANS: &str =
+    "[table][tr][td colspan=0 rowspan=5000]a[/td][td colspan=999999999999]b[/td][/tr][/table]";
+
+#[test]
+pub fn table_spans() {
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+    assert_eq!(
+        serializer.serialize(BBParser::new(TABLE_SPANS)),
+        "<table><tr><td rowspan=\"100\">a</td><td colspan=\"100\">b</td></tr></table>"
+    );
+
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::empty();
+    serializer.register_tag(Box::new(builtins::TableTag::default()));
+    serializer.register_tag(Box::new(builtins::TableRowTag::default()));
+    serializer.register_tag(Box::new(builtins::TableCellTag {
+        max_colspan: 4,
+        max_rowspan: 2,
+    }));
+    assert_eq!(
+        serializer.serialize(BBParser::new(
+            "[table][tr][td colspan=9 rowspan=2]a[/td][/tr][/table]"
+        )),
+        "<table><tr><td colspan=\"4\" rowspan=\"2\">a</td></tr></table>"
+    );
+}
+
+const QUOTES: &str = "[quote]a[/quote][quote=John Smith]b[/quote][quote=\"Jane Doe\"]c[/quote][quote name=Ann post=5 time=\"12:00 UTC\"]d[/quote][quote post=6]e[/quote]";
+
+#[test]
+pub fn quotes() {
+    assert_eq!(
+        serialize_all(QUOTES),
+        concat!(
+            "<blockquote>a</blockquote>",
+            "<blockquote><cite>John Smith</cite>b</blockquote>",
+            "<blockquote><cite>Jane Doe</cite>c</blockquote>",
+            "<blockquote data-post=\"5\" data-time=\"12:00 UTC\"><cite>Ann</cite>d</blockquote>",
+            "<blockquote data-post=\"6\">e</blockquote>"
+        )
+    );
+}
+
+const UNSAFE_QUOTES: &str =
+    "[quote='<script>\"x\"</script>' post='\"><script>' time='a\" onclick=\"b']x[/quote]";
+
+#[test]
+pub fn unsafe_quotes() {
+    assert_eq!(
+        serialize_all(UNSAFE_QUOTES),
+        concat!(
+            "<blockquote data-post=\"&quot;&gt;&lt;script&gt;\" data-time=\"a&quot; onclick=&quot;b\">",
+            "<cite>&lt;script&gt;&quot;x&quot;&lt;&#x2F;script&gt;</cite>x</blockquote>"
+        )
+    );
+}
+
+const SPOILERS: &str = "[spoiler]a[/spoiler][spoiler=\"The <end>\"]b[/spoiler][hide= ]c[/hide][collapse=More][quote]d[/quote][/collapse]";
+
+#[test]
+pub fn spoilers() {
+    assert_eq!(
+        serialize_all(SPOILERS),
+        concat!(
+            "<details><summary>Spoiler</summary>a</details>",
+            "<details><summary>The &lt;end&gt;</summary>b</details>",
+            "<details><summary>Spoiler</summary>c</details>",
+            "<details><summary>More</summary><blockquote>d</blockquote></details>"
+        )
+    );
+}
+
+const SPOILERS_NESTED: &str = "[b]a[spoiler]b[/spoiler]c[/b] [h1][spoiler]c[/spoiler][/h1]";
+
+#[test]
+pub fn spoilers_nested() {
+    assert_eq!(
+        serialize_all(SPOILERS_NESTED),
+        concat!(
+            "<b>a</b><details><summary>Spoiler</summary>b</details><b>c</b> ",
+            "<h1>[spoiler]c[&#x2F;spoiler]</h1>"
+        )
+    );
+}
+
+#[test]
+pub fn spoiler_label() {
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::empty();
+    serializer.register_tag(Box::new(builtins::SpoilerTag {
+        label: String::from("Show <more>"),
+    }));
+    serializer.set_newlines(Some(builtins::newlines()));
+
+    assert_eq!(
+        serializer.serialize(BBParser::new("[spoiler]\na\n\nb\n[/spoiler]")),
+        "<details><summary>Show &lt;more&gt;</summary><p>a</p><p>b</p></details>"
+    );
+}
+
+const CODE: &str = "[code]let [b]x[/b] = <y>;[/code] [code=Rust]a  b[/code] [code lang=c++]c[/code]\n[pre]  [i]d[/i]\n\n e  [/pre][pre=rust]fn main() {}\n[/pre]";
+
+#[test]
+pub fn code() {
+    assert_eq!(
+        serialize_with_newlines(CODE),
+        concat!(
+            "<p><code>let [b]x[&#x2F;b] = &lt;y&gt;;</code> <code class=\"language-rust\">a  b</code> ",
+            "<code class=\"language-c++\">c</code></p>",
+            "<pre>  [i]d[&#x2F;i]\n\n e  </pre>",
+            "<pre><code class=\"language-rust\">fn main() {}\n</code></pre>"
+        )
+    );
+}
+
+const UNSAFE_CODE: &str =
+    "[code='rust\" onclick=\"x']a[/code] [pre=\"a b\"][b]b[/b][/pre] [b]x[pre]c[/pre][/b]";
+
+#[test]
+pub fn unsafe_code() {
+    assert_eq!(
+        serialize_all(UNSAFE_CODE),
+        concat!(
+            "[code=&#x27;rust&quot; onclick=&quot;x&#x27;]a[&#x2F;code] ",
+            "[pre=&quot;a b&quot;][b]b[&#x2F;b][&#x2F;pre] <b>x</b><pre>c</pre>"
+        )
+    );
+}
+
+const CODE_CASE: &str =
+    "[code]a[/CODE]b[/code] [url]https://example.com/[/URL][/url] [img]c.png[/IMG][/img]";
+
+#[test]
+pub fn code_case_sensitive() {
+    use crate::{ParserConfig, TagNamePolicy};
+
+    // Under a case sensitive policy, close tags differing in case are just text in unparsed contents.
+    let config = ParserConfig {
+        tag_names: TagNamePolicy {
+            case_sensitive: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+    assert_eq!(
+        serializer.serialize(BBParser::with_config(CODE_CASE, config)),
+        concat!(
+            "<code>a[&#x2F;CODE]b</code> ",
+            "[url]https:&#x2F;&#x2F;example.com&#x2F;[&#x2F;URL][&#x2F;url] ",
+            "[img]c.png[&#x2F;IMG][&#x2F;img]"
+        )
+    );
+}
+
+const DEGRADED: &str = "[font=x;y]a[quote]b[/quote]c[/font] [url=javascript:x]d[quote]e[/quote]f[/url] [list=zz][*]g[quote]h[/quote][/list]";
+
+#[test]
+pub fn degraded() {
+    // Tags written out as text are never closed or opened again, so their source is only written once.
+    assert_eq!(
+        serialize_all(DEGRADED),
+        concat!(
+            "[font=x;y]a<blockquote>b</blockquote>c[&#x2F;font] ",
+            "[url=javascript:x]d<blockquote>e</blockquote>f[&#x2F;url] ",
+            "[list=zz][*]g<blockquote>h</blockquote>[&#x2F;list]"
+        )
+    );
+    assert_eq!(
+        serialize_with_newlines("[b]a[font=x;y]b\n\nc[/font]d[/b]"),
+        "<p><b>a[font=x;y]b</b></p><p><b>c[&#x2F;font]d</b></p>"
+    );
+
+    let parser = BBParser::with_config(
+        "[b]a[i=1]b[/b]c[/i]",
+        crate::ParserConfig {
+            feature_flags: crate::ParserFeature::V1,
+            ..Default::default()
+        },
+    );
+    let mut serializer =
+        HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_core_v1_tags());
+    assert_eq!(serializer.serialize(parser), "<b>a[i=1]b</b>c[&#x2F;i]");
+}
+
+const SIMPLE_ARGUMENTS: &str = "[b=x][/b] [h1=x]t[/h1]";
+
+#[test]
+pub fn simple_arguments() {
+    let parser = BBParser::new(SIMPLE_ARGUMENTS);
+    let mut serializer =
+        HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_core_v1_tags());
+
+    assert_eq!(
+        serializer.serialize(parser),
+        "[b=x][&#x2F;b] [h1=x]t[&#x2F;h1]"
+    );
+}
+
+const LIST_ITEMS_OPEN: &str = "[list][*]a[*][b]b[/list]c[/b] [table][tr][td]d[td]e[/tr][/table]";
+
+#[test]
+pub fn list_items_open() {
+    // Without void tags, items are open tags left open, which end at the next item or the end of the list.
+    assert_eq!(
+        serialize_all(LIST_ITEMS_OPEN),
+        concat!(
+            "<ul><li>a</li><li><b>b</b></li></ul><b>c</b> ",
+            "<table><tr><td>d</td><td>e</td></tr></table>"
+        )
+    );
+    assert_eq!(
+        serialize_with_newlines("[list]\n[*]a\n[*]b\n[/list]"),
+        "<ul><li><p>a</p></li><li><p>b</p></li></ul>"
+    );
+}
@@ -0,0 +1,410 @@
+//! Conversion of pasted HTML into BBCode, for storing rich text copied from web pages.
+//!  Only a practical subset of HTML is understood, every other element is dropped while its text is kept.
+use alloc::{string::String, vec::Vec};
+
+use crate::{BBParser, TokenKind};
+
+mod tokenizer;
+use tokenizer::{attributes, HtmlToken, HtmlTokenizer};
+
+/// Converts HTML to BBCode using the core tags, alongside links, images and code.
+/// # Supported elements
+/// | HTML | BBCode |
+/// |------|--------|
+/// | `b`, `strong` | `[b]` |
+/// | `i`, `em` | `[i]` |
+/// | `u` | `[u]` |
+/// | `s`, `strike`, `del` | `[s]` |
+/// | `a href` | `[url=href]` |
+/// | `img src alt` | `[img alt=alt]src[/img]` |
+/// | `blockquote` | `[quote]` |
+/// | `pre` | `[pre]` |
+/// | `code` | `[code]` |
+/// | `h1` to `h6` | `[h1]` to `[h6]` |
+/// | `ul`, `ol`, `li` | `[list]`, `[list=1]`, `[*]` |
+/// | `br` | `[br/]` |
+/// | `p` | A blank line between paragraphs |
+///
+/// The contents of `script`, `style`, `textarea` and `title` are dropped entirely.
+#[derive(Clone, Debug)]
+pub struct HtmlImporter<'a> {
+    /// A noparse tag to wrap text that would otherwise be parsed as BBCode in, so it stays text.
+    /// It must be rendered as text, as the built-in `NoParseTag`s do. Such text is written verbatim if `None`.
+    pub text_wrapper: Option<&'a str>,
+    /// Whether or not to only write the core v1.0.0 tags, as rendered by `all_core_v1_tags`.
+    /// Links, code and preformatted text then only keep their text, and images their alt text.
+    pub core_only: bool,
+}
+
+impl<'a> Default for HtmlImporter<'a> {
+    fn default() -> Self {
+        Self {
+            text_wrapper: Some("noparse"),
+            core_only: false,
+        }
+    }
+}
+
+/// Elements that convert directly to a BBCode tag, as `(element, tag)` pairs.
+const ELEMENTS: &[(&str, &str)] = &[
+    ("b", "b"),
+    ("strong", "b"),
+    ("i", "i"),
+    ("em", "i"),
+    ("u", "u"),
+    ("s", "s"),
+    ("strike", "s"),
+    ("del", "s"),
+    ("blockquote", "quote"),
+    ("pre", "pre"),
+    ("code", "code"),
+    ("h1", "h1"),
+    ("h2", "h2"),
+    ("h3", "h3"),
+    ("h4", "h4"),
+    ("h5", "h5"),
+    ("h6", "h6"),
+    ("ul", "list"),
+    ("ol", "list"),
+    ("a", "url"),
+];
+
+/// Tags written outside of the core v1.0.0 tags.
+const EXTENDED_TAGS: &[&str] = &["url", "img", "pre", "code"];
+
+/// Elements whose contents are written verbatim, as their tags are not parsed.
+const VERBATIM_ELEMENTS: &[&str] = &["pre", "code"];
+
+/// Elements whose contents are dropped.
+const HIDDEN_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+impl<'a> HtmlImporter<'a> {
+    /// Convert the given HTML to BBCode.
+    /// # Remarks
+    /// Whitespace is collapsed as a browser would, except within `pre`. Misnested and unclosed elements are closed as needed,
+    /// and end tags without a matching start tag are dropped, so the output is always balanced.
+    ///
+    /// Text within `pre` and `code` never closes them early. Where it holds their own close tag, the tag is closed before it
+    /// and opened again after its `[`, as there's no way to escape it.
+    pub fn import(&self, html: &str) -> String {
+        let mut state = State {
+            importer: self,
+            out: String::with_capacity(html.len()),
+            text: String::new(),
+            open: Vec::new(),
+            pending_space: false,
+            pending_breaks: 0,
+            after_open: true,
+            verbatim: None,
+            hidden: None,
+        };
+
+        for tk in HtmlTokenizer::new(html) {
+            match tk {
+                HtmlToken::Text(text) => state.push_text(text),
+                HtmlToken::Start { name, attrs } => state.start(name, attrs),
+                HtmlToken::End { name } => state.end(&name),
+            }
+        }
+
+        state.flush_text();
+        while let Some((_, tag)) = state.open.pop() {
+            state.write_close(tag);
+        }
+
+        state.out
+    }
+}
+
+struct State<'i, 'a> {
+    importer: &'i HtmlImporter<'a>,
+    out: String,
+    /// Text not yet written out, as it may need wrapping.
+    text: String,
+    /// Open elements that were converted, as `(element, tag)` pairs.
+    open: Vec<(String, &'static str)>,
+    pending_space: bool,
+    pending_breaks: usize,
+    /// Whether nothing but open tags has been written since the last text, so leading whitespace can be dropped.
+    after_open: bool,
+    /// The verbatim element currently open, within which tags are ignored.
+    verbatim: Option<String>,
+    /// The hidden element currently open, within which everything is dropped.
+    hidden: Option<String>,
+}
+
+impl<'i, 'a> State<'i, 'a> {
+    fn push_text(&mut self, raw: &str) {
+        if self.hidden.is_some() {
+            return;
+        }
+
+        let text = html_escape::decode_html_entities(raw);
+
+        if self.verbatim.is_some() {
+            // A newline directly after `<pre>` is ignored by browsers.
+            let text = if self.after_open {
+                text.strip_prefix('\n').unwrap_or(&text)
+            } else {
+                &text
+            };
+            self.after_open &= text.is_empty();
+            self.text.push_str(text);
+            return;
+        }
+
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.pending_space = true;
+                continue;
+            }
+
+            if !self.after_open {
+                if self.pending_breaks > 0 {
+                    self.text
+                        .extend(core::iter::repeat('\n').take(self.pending_breaks));
+                } else if self.pending_space {
+                    self.text.push(' ');
+                }
+            }
+
+            self.pending_breaks = 0;
+            self.pending_space = false;
+            self.after_open = false;
+            self.text.push(c);
+        }
+    }
+
+    fn start(&mut self, name: String, attrs: &str) {
+        if self.hidden.is_some() {
+            return;
+        }
+
+        if self.verbatim.is_some() {
+            if name == "br" {
+                self.text.push('\n');
+            }
+            return;
+        }
+
+        if HIDDEN_ELEMENTS.contains(&name.as_str()) {
+            self.hidden = Some(name);
+            return;
+        }
+
+        match name.as_str() {
+            "p" | "div" => self.request_breaks(if name == "p" { 2 } else { 1 }),
+            "br" => {
+                self.write_open("br/", "");
+                self.after_open = true;
+            }
+            "li" => {
+                if self.open.iter().any(|(_, tag)| *tag == "list") {
+                    self.write_open("*", "");
+                }
+            }
+            "img" if self.importer.core_only => {
+                if let Some((_, alt)) = attributes(attrs).find(|(key, _)| key == "alt") {
+                    self.push_text(alt);
+                }
+            }
+            "img" => {
+                let mut src = None;
+                let mut alt = None;
+                for (key, value) in attributes(attrs) {
+                    match key.as_str() {
+                        "src" => src = Some(value),
+                        "alt" => alt = Some(value),
+                        _ => {}
+                    }
+                }
+
+                if let Some(src) = src.map(|x| encode_url(&html_escape::decode_html_entities(x))) {
+                    let alt = alt
+                        .map(|x| arg_value(&html_escape::decode_html_entities(x)))
+                        .filter(|x| !x.is_empty())
+                        .map(|x| [" alt=", &x].concat())
+                        .unwrap_or_default();
+
+                    self.write_open("img", &alt);
+                    self.out.push_str(&src);
+                    self.write_close("img");
+                }
+            }
+            _ => {
+                let Some((_, tag)) = ELEMENTS.iter().find(|(el, _)| *el == name) else {
+                    // Unsupported, only the text is kept.
+                    return;
+                };
+                if self.importer.core_only && EXTENDED_TAGS.contains(tag) {
+                    return;
+                }
+
+                let args = match name.as_str() {
+                    "ol" => String::from("=1"),
+                    "a" => {
+                        let Some((_, href)) = attributes(attrs).find(|(key, _)| key == "href")
+                        else {
+                            return;
+                        };
+                        ["=", &encode_url(&html_escape::decode_html_entities(href))].concat()
+                    }
+                    _ => String::new(),
+                };
+
+                if VERBATIM_ELEMENTS.contains(&name.as_str()) {
+                    self.verbatim = Some(name.clone());
+                }
+                self.write_open(tag, &args);
+                self.open.push((name, tag));
+            }
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        if let Some(hidden) = &self.hidden {
+            if hidden == name {
+                self.hidden = None;
+            }
+            return;
+        }
+
+        if let Some(verbatim) = &self.verbatim {
+            if verbatim != name {
+                return;
+            }
+            self.flush_text();
+            self.verbatim = None;
+        }
+
+        match name {
+            "p" => self.request_breaks(2),
+            "div" => self.request_breaks(1),
+            _ => {
+                let Some(idx) = self.open.iter().rposition(|(el, _)| el == name) else {
+                    return;
+                };
+
+                self.flush_text();
+                for (_, tag) in self.open.drain(idx..).rev().collect::<Vec<_>>() {
+                    self.write_close(tag);
+                }
+            }
+        }
+    }
+
+    /// Require the given number of line breaks before any following text, dropping any whitespace.
+    fn request_breaks(&mut self, count: usize) {
+        self.pending_breaks = self.pending_breaks.max(count);
+        self.pending_space = false;
+    }
+
+    fn write_open(&mut self, tag: &str, args: &str) {
+        // Whitespace before an open tag is still written, as it is between two words.
+        if !self.after_open {
+            if self.pending_breaks > 0 {
+                self.text
+                    .extend(core::iter::repeat('\n').take(self.pending_breaks));
+            } else if self.pending_space {
+                self.text.push(' ');
+            }
+        }
+        self.pending_breaks = 0;
+        self.pending_space = false;
+
+        self.flush_text();
+        self.out.extend(["[", tag, args, "]"]);
+        self.after_open = true;
+    }
+
+    fn write_close(&mut self, tag: &str) {
+        self.out.extend(["[/", tag, "]"]);
+        self.after_open = false;
+    }
+
+    /// Write out the pending text, wrapping it if it would be parsed as BBCode.
+    fn flush_text(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+
+        let text = core::mem::take(&mut self.text);
+
+        if self.verbatim.is_some() {
+            // The verbatim tag is already open.
+            let tag = self.open.last().map_or("", |(_, tag)| *tag);
+            write_unparsed(tag, &text, &mut self.out);
+            return;
+        }
+
+        let is_markup = BBParser::new(&text).any(|x| !matches!(x.kind, TokenKind::Text));
+
+        match self.importer.text_wrapper {
+            Some(wrapper) if is_markup => {
+                self.out.extend(["[", wrapper, "]"]);
+                write_unparsed(wrapper, &text, &mut self.out);
+                self.out.extend(["[/", wrapper, "]"]);
+            }
+            _ => self.out.push_str(&text),
+        }
+    }
+}
+
+/// Write the given text within an open tag whose contents are not parsed, such that all of it stays text.
+/// # Remarks
+/// Tags can't be escaped, so where the text holds the tag's own close tag, which would end it early, the tag is closed
+/// before it and opened again after its `[`, leaving that `[` as text in between.
+fn write_unparsed(tag: &str, mut text: &str, out: &mut String) {
+    while let Some(idx) = BBParser::new(text)
+        .find(|x| x.is_close(tag))
+        .map(|x| x.start)
+    {
+        out.extend([&text[..idx], "[/", tag, "][[", tag, "]"]);
+        text = &text[(idx + 1)..];
+    }
+
+    out.push_str(text);
+}
+
+/// Percent-encode the characters in a URL that could end a BBCode tag or argument early.
+fn encode_url(url: &str) -> String {
+    let mut out = String::with_capacity(url.len());
+
+    for c in url.trim().chars() {
+        match c {
+            ' ' => out.push_str("%20"),
+            '"' => out.push_str("%22"),
+            '\'' => out.push_str("%27"),
+            '[' => out.push_str("%5B"),
+            ']' => out.push_str("%5D"),
+            c if c.is_whitespace() => {}
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Make the given text safe to use as a tag argument value, quoting it if needed.
+fn arg_value(value: &str) -> String {
+    // Brackets would end the tag even when quoted, and there's no way to escape them.
+    let value: String = value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('[', "%5B")
+        .replace(']', "%5D");
+
+    if !value.contains(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '=')) {
+        return value;
+    }
+
+    if value.contains('"') {
+        ["'", &value.replace('\'', ""), "'"].concat()
+    } else {
+        ["\"", &value, "\""].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,156 @@
+use alloc::string::String;
+
+#[cfg(feature = "html_gen")]
+use crate::{
+    html::{builtins, HtmlSerializer, SimpleHtmlWriter},
+    BBParser,
+};
+
+use super::HtmlImporter;
+
+fn import(html: &str) -> String {
+    HtmlImporter::default().import(html)
+}
+
+#[cfg(feature = "html_gen")]
+fn render(bbcode: &str) -> String {
+    let mut serializer =
+        HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_core_v1_tags());
+    serializer.serialize(BBParser::new(bbcode))
+}
+
+#[cfg(feature = "html_gen")]
+const ROUND_TRIP: &str = "<b>Bold</b> and <i>italic</i> <u>under</u> <s>struck</s><h2>Heading</h2><blockquote>Quoted<br/>text</blockquote>";
+
+#[cfg(feature = "html_gen")]
+#[test]
+pub fn round_trip() {
+    let bbcode = import(ROUND_TRIP);
+    assert_eq!(
+        bbcode,
+        "[b]Bold[/b] and [i]italic[/i] [u]under[/u] [s]struck[/s][h2]Heading[/h2][quote]Quoted[br/]text[/quote]"
+    );
+    assert_eq!(render(&bbcode), ROUND_TRIP);
+}
+
+#[cfg(feature = "html_gen")]
+const ALIASES: &str = "<strong>a</strong> <em>b</em> <del>c</del> <strike>d</strike>";
+
+#[cfg(feature = "html_gen")]
+#[test]
+pub fn aliases() {
+    assert_eq!(
+        render(&import(ALIASES)),
+        "<b>a</b> <i>b</i> <s>c</s> <s>d</s>"
+    );
+}
+
+const PASTED: &str = r#"<!DOCTYPE html>
+<html><head><title>Page</title><style>p { color: red; }</style></head>
+<body>
+  <!-- comment -->
+  <p>First   paragraph,
+     with <span class="x">a span</span>.</p>
+  <p>Second &amp; <a href="https://example.com/a b">a link</a><script>alert("[b]")</script></p>
+  <ul><li>One</li><li>Two</li></ul>
+</body></html>"#;
+
+#[test]
+pub fn pasted() {
+    assert_eq!(
+        import(PASTED),
+        "First paragraph, with a span.\n\nSecond & [url=https://example.com/a%20b]a link[/url]\n\n[list][*]One[*]Two[/list]"
+    );
+}
+
+const VERBATIM: &str =
+    "<pre><code>\nfn main() {\n    [b]x[/b] &lt; 1\n}</code></pre> <code>[i]</code>";
+
+#[test]
+pub fn verbatim() {
+    assert_eq!(
+        import(VERBATIM),
+        "[pre]fn main() {\n    [b]x[/b] < 1\n}[/pre] [code][i][/code]"
+    );
+}
+
+const MARKUP_TEXT: &str = "<p>Type [b]bold[/b] to get <b>bold</b></p>";
+
+#[test]
+pub fn markup_text() {
+    assert_eq!(
+        import(MARKUP_TEXT),
+        "[noparse]Type [b]bold[/b] to get [/noparse][b]bold[/b]"
+    );
+}
+
+const CLOSE_IN_TEXT: &str = "<pre>x[/pre][url=https://evil]y[/url]</pre><p>a[/NOPARSE][b]b[/b]</p>";
+
+#[test]
+pub fn close_in_text() {
+    // The text can't close the tags it's written in early, so none of it is parsed.
+    assert_eq!(
+        import(CLOSE_IN_TEXT),
+        "[pre]x[/pre][[pre]/pre][url=https://evil]y[/url][/pre][noparse]\n\na[/noparse][[noparse]/NOPARSE][b]b[/b][/noparse]"
+    );
+}
+
+#[cfg(feature = "html_gen")]
+#[test]
+pub fn markup_text_rendered() {
+    assert_eq!(
+        render(&import(MARKUP_TEXT)),
+        "Type [b]bold[&#x2F;b] to get <b>bold</b>"
+    );
+
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+    assert_eq!(
+        serializer.serialize(BBParser::new(&import(CLOSE_IN_TEXT))),
+        "<pre>x</pre>[<pre>&#x2F;pre][url=https:&#x2F;&#x2F;evil]y[&#x2F;url]</pre>\n\na[&#x2F;NOPARSE][b]b[&#x2F;b]"
+    );
+}
+
+const MISNESTED: &str = "<b>a<i>b</b>c</i></u><img src=\"cat.png\" alt='A \"cat\"'><a>no href</a>";
+
+#[test]
+pub fn misnested() {
+    assert_eq!(
+        import(MISNESTED),
+        "[b]a[i]b[/i][/b]c[img alt='A \"cat\"']cat.png[/img]no href"
+    );
+}
+
+#[cfg(feature = "html_gen")]
+const EVERY_ELEMENT: &str = "<p><b>b</b><strong>strong</strong><i>i</i><em>em</em><u>u</u><s>s</s><strike>strike</strike>\
+    <del>del</del><a href=\"https://example.com\">a</a><img src=\"cat.png\" alt=\"img\"><br></p><blockquote>quote</blockquote>\
+    <pre>pre</pre><code>code</code><h1>h1</h1><h2>h2</h2><h3>h3</h3><h4>h4</h4><h5>h5</h5><h6>h6</h6>\
+    <ul><li>ul</li></ul><ol><li>ol</li></ol>";
+
+#[cfg(feature = "html_gen")]
+#[test]
+pub fn every_element_rendered() {
+    // Every tag written is rendered by the tags targeted, so none are left as text.
+    let core = HtmlImporter {
+        core_only: true,
+        ..Default::default()
+    };
+    let rendered = render(&core.import(EVERY_ELEMENT));
+    assert_eq!(
+        rendered,
+        "<b>b</b><b>strong</b><i>i</i><i>em</i><u>u</u><s>s</s><s>strike</s><s>del</s>aimg<br/>\
+         <blockquote>quote</blockquote>precode<h1>h1</h1><h2>h2</h2><h3>h3</h3><h4>h4</h4><h5>h5</h5><h6>h6</h6>\
+         <ul><li>ul</li></ul><ol><li>ol</li></ol>"
+    );
+
+    let mut serializer = HtmlSerializer::<SimpleHtmlWriter>::with_tags(builtins::all_tags());
+    let rendered = serializer.serialize(BBParser::new(&import(EVERY_ELEMENT)));
+    assert!(!rendered.contains('['), "{rendered}");
+}
+
+#[test]
+pub fn bracket_args() {
+    assert_eq!(
+        import("<img src=\"a.png\" alt=\"a]b [c]\">"),
+        "[img alt=\"a%5Db %5Bc%5D\"]a.png[/img]"
+    );
+}
@@ -0,0 +1,188 @@
+use alloc::string::String;
+
+/// A single piece of an HTML document, as produced by [HtmlTokenizer].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum HtmlToken<'a> {
+    /// Raw text, with entities still encoded.
+    Text(&'a str),
+    /// A start tag, with its lowercased name and raw attribute text.
+    Start { name: String, attrs: &'a str },
+    /// An end tag, with its lowercased name.
+    End { name: String },
+}
+
+/// Splits a practical subset of HTML into [HtmlToken]s. Comments, doctypes and processing instructions are skipped.
+/// # Remarks
+/// This is not a conforming HTML tokenizer, anything that doesn't look like a tag is treated as text.
+pub(super) struct HtmlTokenizer<'a> {
+    rest: &'a str,
+    /// The name of the raw text element (such as `script`) currently open, whose contents are never parsed.
+    raw_text: Option<String>,
+}
+
+impl<'a> HtmlTokenizer<'a> {
+    pub(super) fn new(input: &'a str) -> Self {
+        Self {
+            rest: input,
+            raw_text: None,
+        }
+    }
+
+    /// Elements whose contents are text, even if they look like tags.
+    const RAW_TEXT_ELEMENTS: &'static [&'static str] = &["script", "style", "textarea", "title"];
+
+    /// Skip past the next occurence of `end`, or to the end of the input.
+    fn skip_past(&mut self, end: &str) {
+        self.rest = self
+            .rest
+            .find(end)
+            .map_or("", |idx| &self.rest[(idx + end.len())..]);
+    }
+
+    fn read_raw_text(&mut self, name: &str) -> HtmlToken<'a> {
+        let mut end = 0;
+
+        // Find `</name`, compared ignoring case.
+        while let Some(idx) = self.rest[end..].find("</") {
+            let after = &self.rest[(end + idx + 2)..];
+            if after
+                .get(..name.len())
+                .map_or(false, |x| x.eq_ignore_ascii_case(name))
+            {
+                end += idx;
+                break;
+            }
+            end += idx + 2;
+        }
+
+        if !self.rest[end..].starts_with("</") {
+            end = self.rest.len();
+        }
+
+        let (text, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        HtmlToken::Text(text)
+    }
+
+    /// Attempt to read a tag from the start of the input, which begins with `<`.
+    fn read_tag(&mut self) -> Option<HtmlToken<'a>> {
+        let inner = &self.rest[1..];
+        let (is_end, inner) = match inner.strip_prefix('/') {
+            Some(inner) => (true, inner),
+            None => (false, inner),
+        };
+
+        let name_len = inner
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(inner.len());
+        if name_len == 0 || !inner.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let name = inner[..name_len].to_ascii_lowercase();
+
+        // Find the end of the tag, skipping over quoted attribute values.
+        let mut quote = None;
+        let end = inner[name_len..].char_indices().find_map(|(idx, c)| {
+            match (quote, c) {
+                (None, '"' | '\'') => quote = Some(c),
+                (Some(q), c) if q == c => quote = None,
+                (None, '>') => return Some(name_len + idx),
+                _ => {}
+            }
+            None
+        })?;
+
+        let attrs = inner[name_len..end].trim();
+        let attrs = attrs.strip_suffix('/').unwrap_or(attrs).trim_end();
+        self.rest = &inner[(end + 1)..];
+
+        if is_end {
+            Some(HtmlToken::End { name })
+        } else {
+            if Self::RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+                self.raw_text = Some(name.clone());
+            }
+            Some(HtmlToken::Start { name, attrs })
+        }
+    }
+}
+
+impl<'a> Iterator for HtmlTokenizer<'a> {
+    type Item = HtmlToken<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            if let Some(name) = self.raw_text.take() {
+                let text = self.read_raw_text(&name);
+                if text != HtmlToken::Text("") {
+                    return Some(text);
+                }
+                continue;
+            }
+
+            if self.rest.starts_with("<!--") {
+                self.skip_past("-->");
+                continue;
+            }
+
+            if self.rest.starts_with("<!") || self.rest.starts_with("<?") {
+                self.skip_past(">");
+                continue;
+            }
+
+            if self.rest.starts_with('<') {
+                if let Some(tag) = self.read_tag() {
+                    return Some(tag);
+                }
+            }
+
+            // Text runs until the next `<`, not counting one at the very start that failed to be a tag.
+            let end = self.rest[1..].find('<').map_or(self.rest.len(), |x| x + 1);
+            let (text, rest) = self.rest.split_at(end);
+            self.rest = rest;
+            return Some(HtmlToken::Text(text));
+        }
+    }
+}
+
+/// Iterator over the attributes in the raw attribute text of a start tag, as `(lowercased name, raw value)` pairs.
+/// Attributes without a value have an empty one.
+pub(super) fn attributes(mut attrs: &str) -> impl Iterator<Item = (String, &str)> {
+    core::iter::from_fn(move || {
+        attrs = attrs.trim_start();
+        if attrs.is_empty() {
+            return None;
+        }
+
+        let name_len = attrs
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(attrs.len())
+            .max(1);
+        let name = attrs[..name_len].to_ascii_lowercase();
+        attrs = attrs[name_len..].trim_start();
+
+        let Some(rest) = attrs.strip_prefix('=') else {
+            return Some((name, ""));
+        };
+        attrs = rest.trim_start();
+
+        let quote = attrs.chars().next().filter(|c| matches!(c, '"' | '\''));
+        let value = if let Some(quote) = quote {
+            let end = attrs[1..].find(quote).map_or(attrs.len(), |x| x + 1);
+            let value = &attrs[1..end];
+            attrs = attrs.get((end + 1)..).unwrap_or("");
+            value
+        } else {
+            let end = attrs.find(char::is_whitespace).unwrap_or(attrs.len());
+            let (value, rest) = attrs.split_at(end);
+            attrs = rest;
+            value
+        };
+
+        Some((name, value))
+    })
+}
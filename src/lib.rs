@@ -24,33 +24,83 @@ println!("Document:");
 println!("{}", serializer.serialize(parser));
 ```"#
 )]
+#![cfg_attr(
+    feature = "markdown_gen",
+    doc = r#"## Markdown output
+```rust
+# use bbx::{BBParser, markdown::{*, builtins::*}};
+# const input: &str = "[title]This is a test document![/title]";
+let mut serializer: MarkdownSerializer<SimpleMarkdownWriter> =
+    MarkdownSerializer::with_tags(all_tags());
+println!("{}", serializer.serialize(BBParser::new(input)));
+```"#
+)]
+#![cfg_attr(
+    feature = "text_gen",
+    doc = r#"## Plain text output
+```rust
+# use bbx::{BBParser, text::TextSerializer};
+# const input: &str = "[quote]Hello![/quote]Hi there, [b]friend[/b].";
+// Visible text only, for search indexing or previews.
+let serializer = TextSerializer::default();
+println!("{}", serializer.serialize(BBParser::new(input)));
+```"#
+)]
 //! # `no_std`
 #![cfg_attr(
     not(feature = "alloc"),
     doc = "This feature set is `no_std` compatible, should you want that."
 )]
 #![cfg_attr(
-    all(feature = "alloc", not(feature = "html_gen")),
+    all(
+        feature = "alloc",
+        not(any(feature = "html_gen", feature = "markdown_gen"))
+    ),
     doc = "This feature set is not `no_std` compatible but is `alloc` compatible, due to the following features:"
 )]
 #![cfg_attr(
-    all(feature = "alloc", feature = "html_gen"),
+    all(feature = "alloc", any(feature = "html_gen", feature = "markdown_gen")),
     doc = "This feature set is `std` (hosted) only, due to the following features:"
 )]
 #![cfg_attr(feature = "track_open_tags", doc = "- `track_open_tags`")]
 #![cfg_attr(feature = "parser_rules", doc = "- `parser_rules`")]
 #![cfg_attr(feature = "html_gen", doc = "- `html_gen` (required `std`!)")]
-#![cfg_attr(not(all(feature = "alloc", feature = "html_gen")), no_std)]
+#![cfg_attr(feature = "html_import", doc = "- `html_import`")]
+#![cfg_attr(feature = "markdown_gen", doc = "- `markdown_gen` (required `std`!)")]
+#![cfg_attr(feature = "text_gen", doc = "- `text_gen`")]
+#![cfg_attr(feature = "bbcode_gen", doc = "- `bbcode_gen`")]
+#![cfg_attr(feature = "truncate", doc = "- `truncate`")]
+#![cfg_attr(
+    not(all(feature = "alloc", any(feature = "html_gen", feature = "markdown_gen"))),
+    no_std
+)]
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
 extern crate alloc;
 
 mod parser;
 
-pub use parser::{BBParser, BBTag, ParserConfig, ParserFeature, Token, TokenKind};
+pub use parser::{
+    BBParser, BBTag, ParserConfig, ParserFeature, TagArg, TagArgs, TagNamePolicy, Token, TokenKind,
+};
+
+#[cfg(feature = "alloc")]
+pub use parser::adapters;
 
 #[cfg(feature = "parser_rules")]
 pub use parser::rules;
 
+#[cfg(feature = "bbcode_gen")]
+pub mod bbcode;
 #[cfg(feature = "html_gen")]
 pub mod html;
+#[cfg(feature = "html_import")]
+pub mod import;
+#[cfg(feature = "markdown_gen")]
+pub mod markdown;
+#[cfg(any(feature = "html_gen", feature = "markdown_gen"))]
+pub mod render;
+#[cfg(feature = "text_gen")]
+pub mod text;
+#[cfg(feature = "truncate")]
+pub mod truncate;
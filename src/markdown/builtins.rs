@@ -0,0 +1,1008 @@
+//! Built-in Markdown implementations of the common BBCode tags, mirroring [crate::html::builtins] where possible.
+//!  Tags with no Markdown equivalent are written as inline HTML, which CommonMark passes through.
+
+use core::fmt;
+
+use crate::{
+    render::ContentModel,
+    rules::{builtin::NoParseRule, ParserRule, ParserRuleObjBox},
+    BBTag, Token, TokenKind,
+};
+
+use super::{Markdown, MarkdownOutput, TagWriter, TokenWriter};
+
+macro_rules! tag_list {
+    ($ct:ident; $($tag:ident),*) => {
+        {
+            let v: Vec<Box<dyn TagWriter<Markdown, $ct>>> = vec![
+                $(
+                    Box::new($tag::default()),
+                )*
+            ];
+
+            v
+        }
+    };
+}
+
+/// Returns all built-in tags from v1.0.0 (or earlier) of the library that are considered "basic" and safe for all usages by the authors.
+/// # "Safe"
+/// See [crate::html::builtins::all_core_v1_tags], this is the same set of tags except for lists, which are only in
+/// [all_tags].
+/// # Included tags
+/// - [BoldTag]
+/// - [ItalicTag]
+/// - [UnderlineTag]
+/// - [StrikethroughTag]
+/// - [LinebreakTag]
+/// - [QuoteTag]
+/// - [BlockQuoteTag]
+/// - [SubscriptTag]
+/// - [SuperscriptTag]
+/// - [Header1Tag]
+/// - [Header2Tag]
+/// - [Header3Tag]
+/// - [Header4Tag]
+/// - [Header5Tag]
+/// - [Header6Tag]
+/// - [CenterTag]
+/// - [NoParseTag]
+pub fn all_core_v1_tags<CustomTy>() -> Vec<Box<dyn TagWriter<Markdown, CustomTy>>>
+where
+    CustomTy: Clone + Default + 'static,
+{
+    tag_list! {CustomTy;
+        BoldTag,
+        ItalicTag,
+        UnderlineTag,
+        StrikethroughTag,
+        LinebreakTag,
+        QuoteTag,
+        BlockQuoteTag,
+        SubscriptTag,
+        SuperscriptTag,
+        Header1Tag,
+        Header2Tag,
+        Header3Tag,
+        Header4Tag,
+        Header5Tag,
+        Header6Tag,
+        CenterTag,
+        NoParseTag
+    }
+}
+
+/// Returns every built-in Markdown tag, being [all_core_v1_tags] alongside code, lists, links and images.
+/// # Remarks
+/// Links and images are only written for `http`, `https`, `mailto` and relative URLs, otherwise their text is written instead.
+pub fn all_tags<CustomTy>() -> Vec<Box<dyn TagWriter<Markdown, CustomTy>>>
+where
+    CustomTy: Clone + Default + 'static,
+{
+    let mut tags = all_core_v1_tags();
+    tags.append(&mut tag_list! {CustomTy;
+        LeftTag,
+        RightTag,
+        KbdTag,
+        CodeTag,
+        PreformattedTag,
+        ListTag,
+        ListItemTag,
+        LinkTag,
+        ImageTag
+    });
+    tags
+}
+
+/// Shared [TagWriter] implementation of the tags declared with `simple_tag!`.
+/// # Remarks
+/// This can't be a blanket implementation, as it would conflict with the other tags'.
+struct Simple<T>(core::marker::PhantomData<T>);
+
+impl<T> Simple<T> {
+    fn open_tag<CustomTy>(
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> Result<bool, fmt::Error>
+    where
+        CustomTy: Clone,
+        T: SimpleMarkdownTagWriter<CustomTy>,
+    {
+        if token.args().is_some() {
+            tk_writer.write_token(token, out)?;
+            return Ok(false);
+        }
+
+        match T::KIND {
+            MarkdownKind::Inline => {}
+            MarkdownKind::Block => out.ensure_blank_line(),
+            MarkdownKind::Prefixed => {
+                out.ensure_blank_line();
+                out.push_prefix(T::OPEN);
+                return Ok(true);
+            }
+        }
+        out.push_str(T::OPEN);
+        if T::KIND == MarkdownKind::Block && T::CLOSE.is_some() {
+            // HTML blocks need a blank line after them for their contents to still be read as Markdown.
+            out.ensure_blank_line();
+        }
+        Ok(true)
+    }
+
+    fn close_tag<CustomTy>(
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        open_token: &Token<'_, CustomTy>,
+        close_token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result
+    where
+        CustomTy: Clone,
+        T: SimpleMarkdownTagWriter<CustomTy>,
+    {
+        if open_token.args().is_some() || close_token.args().is_some() {
+            return tk_writer.write_token(close_token, out);
+        }
+
+        match T::KIND {
+            MarkdownKind::Inline => {}
+            MarkdownKind::Block => out.ensure_blank_line(),
+            MarkdownKind::Prefixed => {
+                out.ensure_line_start();
+                out.pop_prefix();
+            }
+        }
+        if let Some(close) = T::CLOSE {
+            out.push_str(close);
+        }
+        if T::KIND != MarkdownKind::Inline {
+            out.ensure_blank_line();
+        }
+        Ok(())
+    }
+}
+
+/// How a [SimpleMarkdownTagWriter] is laid out.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum MarkdownKind {
+    /// Written in place, such as emphasis.
+    Inline,
+    /// Written on their own lines, separated from the surrounding text by blank lines.
+    Block,
+    /// A block whose open "tag" is a prefix for each line of its contents, such as quotes.
+    Prefixed,
+}
+
+trait SimpleMarkdownTagWriter<CustomTy>
+where
+    CustomTy: Clone,
+{
+    const KIND: MarkdownKind;
+
+    const OPEN: &'static str;
+
+    const CLOSE: Option<&'static str>;
+}
+
+macro_rules! simple_tag {
+    ($doc:expr, $name:ident, $tags:expr, $kind:ident, $open:expr, $close:expr) => {
+        #[derive(Copy, Clone, Debug, Default)]
+        #[doc = $doc]
+        #[doc = "<br/>"]
+        #[doc = "This matches the following BBCode tags: `"]
+        #[doc = stringify!($tags)]
+        #[doc = "`"]
+        pub struct $name<CustomTy = ()> {
+            _custom_ty: core::marker::PhantomData<CustomTy>,
+        }
+
+        impl<CustomTy> SimpleMarkdownTagWriter<CustomTy> for $name<CustomTy>
+        where
+            CustomTy: Clone,
+        {
+            const KIND: MarkdownKind = MarkdownKind::$kind;
+            const OPEN: &'static str = $open;
+            const CLOSE: Option<&'static str> = $close;
+        }
+
+        impl<CustomTy> TagWriter<Markdown, CustomTy> for $name<CustomTy>
+        where
+            CustomTy: Clone + 'static,
+        {
+            fn match_tag(&self, tag: &str) -> bool {
+                self.match_tag_with(tag, &Default::default())
+            }
+
+            fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+                $tags.iter().any(|x| policy.names_match(x, tag))
+            }
+
+            fn open_tag(
+                &self,
+                tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+                token: &Token<'_, CustomTy>,
+                out: &mut MarkdownOutput,
+            ) -> Result<bool, fmt::Error> {
+                Simple::<Self>::open_tag(tk_writer, token, out)
+            }
+
+            fn close_tag<'a>(
+                &self,
+                tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+                open_token: &Token<'a, CustomTy>,
+                close_token: &Token<'a, CustomTy>,
+                out: &mut MarkdownOutput,
+            ) -> fmt::Result {
+                Simple::<Self>::close_tag(tk_writer, open_token, close_token, out)
+            }
+
+            fn standalone_tag(
+                &self,
+                tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+                token: &Token<'_, CustomTy>,
+                out: &mut MarkdownOutput,
+            ) -> fmt::Result {
+                tk_writer.write_token(token, out)
+            }
+        }
+    };
+}
+
+// "Safe" tags, as per the definition in all_core_v1_tags.
+simple_tag! {
+"A bold tag with no arguments, which converts to `**strong emphasis**`.",
+BoldTag, ["b", "bold"], Inline, "**", Some("**")
+}
+simple_tag! {
+"An italic tag with no arguments, which converts to `*emphasis*`.",
+ItalicTag, ["i", "italic"], Inline, "*", Some("*")
+}
+simple_tag! {
+"An underline tag with no arguments, which has no Markdown equivalent and converts to inline HTML5 `<u>`.",
+UnderlineTag, ["u", "underline", "under"], Inline, "<u>", Some("</u>")
+}
+simple_tag! {
+"A strikethrough tag with no arguments, which converts to `~~strikethrough~~` (a widely supported extension to CommonMark).",
+StrikethroughTag, ["s", "del", "strike"], Inline, "~~", Some("~~")
+}
+simple_tag! {
+"Inline quote tag with no arguments, which has no Markdown equivalent and converts to inline HTML5 `<q>`.",
+QuoteTag, ["q"], Inline, "<q>", Some("</q>")
+}
+simple_tag! {
+"Subscript tag with no arguments, which has no Markdown equivalent and converts to inline HTML5 `<sub>`.",
+SubscriptTag, ["sub", "subscript", "small"], Inline, "<sub>", Some("</sub>")
+}
+simple_tag! {
+"Superscript tag with no arguments, which has no Markdown equivalent and converts to inline HTML5 `<sup>`.",
+SuperscriptTag, ["sup", "super", "superscript"], Inline, "<sup>", Some("</sup>")
+}
+simple_tag! {
+"Keypress styling tag with no arguments, which has no Markdown equivalent and converts to inline HTML5 `<kbd>`.",
+KbdTag, ["kbd"], Inline, "<kbd>", Some("</kbd>")
+}
+simple_tag! {
+"A block quote tag with no arguments, which converts to a `> ` prefixed block quote.",
+BlockQuoteTag, ["quote", "blockquote"], Prefixed, "> ", None
+}
+simple_tag! {
+"Header (tier 1) tag with no arguments, which converts to a `# ` ATX heading.",
+Header1Tag, ["h1", "title"], Block, "# ", None
+}
+simple_tag! {
+"Header (tier 2) tag with no arguments, which converts to a `## ` ATX heading.",
+Header2Tag, ["h2", "topic"], Block, "## ", None
+}
+simple_tag! {
+"Header (tier 3) tag with no arguments, which converts to a `### ` ATX heading.",
+Header3Tag, ["h3", "subtopic"], Block, "### ", None
+}
+simple_tag! {
+"Header (tier 4) tag with no arguments, which converts to a `#### ` ATX heading.",
+Header4Tag, ["h4"], Block, "#### ", None
+}
+simple_tag! {
+"Header (tier 5) tag with no arguments, which converts to a `##### ` ATX heading.",
+Header5Tag, ["h5"], Block, "##### ", None
+}
+simple_tag! {
+"Header (tier 6) tag with no arguments, which converts to a `###### ` ATX heading.",
+Header6Tag, ["h6"], Block, "###### ", None
+}
+simple_tag! {
+"Centering tag with no arguments, which has no Markdown equivalent and converts to an HTML block `<div align=\"center\">`.",
+CenterTag, ["center"], Block, "<div align=\"center\">", Some("</div>")
+}
+simple_tag! {
+"Left-align tag with no arguments, which has no Markdown equivalent and converts to an HTML block `<div align=\"left\">`.",
+LeftTag, ["left"], Block, "<div align=\"left\">", Some("</div>")
+}
+simple_tag! {
+"Right-align tag with no arguments, which has no Markdown equivalent and converts to an HTML block `<div align=\"right\">`.",
+RightTag, ["right"], Block, "<div align=\"right\">", Some("</div>")
+}
+
+/// A linebreak tag with no arguments, which converts to a Markdown hard line break (a `\` at the end of the line).
+/// <br/>
+/// This matches the following BBCode tags: `["br"]`
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LinebreakTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Markdown, CustomTy> for LinebreakTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        tag.eq_ignore_ascii_case("br")
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        policy.names_match("br", tag)
+    }
+
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> Result<bool, fmt::Error> {
+        // Often written without a trailing `/`, in which case there's nothing left to close.
+        self.standalone_tag(tk_writer, token, out)?;
+        Ok(token.args().is_none())
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        Ok(())
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        if token.args().is_some() {
+            return tk_writer.write_token(token, out);
+        }
+
+        out.push_str("\\\n");
+        Ok(())
+    }
+}
+
+/// The raw tag name of an open token, for use in a [NoParseRule].
+fn open_tag_name<'a, CustomTy: Clone>(token: &Token<'a, CustomTy>) -> Option<&'a str> {
+    match token.kind {
+        TokenKind::OpenBBTag(BBTag { tag, .. }) => Some(tag),
+        _ => None,
+    }
+}
+
+/// The longest run of consecutive backticks in the given text.
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// Write the given code as a fenced code block with the given language.
+fn write_code_block(code: &str, lang: &str, out: &mut MarkdownOutput) {
+    // A newline directly after the open tag or before the close tag is only there for readability.
+    let code = code.strip_prefix('\n').unwrap_or(code);
+    let code = code.strip_suffix('\n').unwrap_or(code);
+    let fence = "`".repeat((longest_backtick_run(code) + 1).max(3));
+
+    out.ensure_blank_line();
+    out.push_str(&fence);
+    out.push_str(lang);
+    out.push_str("\n");
+    // Blank lines in code are significant.
+    out.begin_verbatim();
+    out.push_str(code);
+    out.ensure_line_start();
+    out.push_str(&fence);
+    out.end_verbatim();
+    out.ensure_blank_line();
+}
+
+/// Code styling tag with no arguments, which converts to a `` `code span` ``, or a fenced code block if it spans multiple lines.
+/// Its contents are not parsed.
+/// <br/>
+/// This matches the following BBCode tags: `["code"]`
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CodeTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Markdown, CustomTy> for CodeTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        tag.eq_ignore_ascii_case("code")
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        policy.names_match("code", tag)
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        _: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> Result<bool, fmt::Error> {
+        out.begin_capture();
+        out.begin_verbatim();
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        out.end_verbatim();
+        let code = out.end_capture();
+        if code.contains('\n') {
+            write_code_block(&code, "", out);
+            return Ok(());
+        }
+        if code.is_empty() {
+            return Ok(());
+        }
+
+        // The fence must be longer than any run of backticks inside, and padded if the code starts or ends with one.
+        let fence = "`".repeat(longest_backtick_run(&code) + 1);
+        let pad = if code.starts_with('`') || code.ends_with('`') {
+            " "
+        } else {
+            ""
+        };
+
+        out.push_str(&fence);
+        out.push_str(pad);
+        out.push_str(&code);
+        out.push_str(pad);
+        out.push_str(&fence);
+        Ok(())
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn try_special<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        self.try_special_with(token, &Default::default())
+    }
+
+    fn try_special_with<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+        policy: &crate::TagNamePolicy,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        open_tag_name(token).map(|tag| NoParseRule::with_policy(tag, *policy).to_box())
+    }
+}
+
+/// No parse tag, which writes its contents as escaped text exactly as they are, without any markup of its own.
+/// <br/>
+/// This matches the following BBCode tags: `["noparse"]`
+/// # Remarks
+/// This is the tag the [Sanitizer][crate::bbcode::Sanitizer] wraps tags kept as text in, so they must be rendered by it
+/// to stay text. Any arguments are ignored.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoParseTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Markdown, CustomTy> for NoParseTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        tag.eq_ignore_ascii_case("noparse")
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        policy.names_match("noparse", tag)
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        _: &Token<'_, CustomTy>,
+        _: &mut MarkdownOutput,
+    ) -> Result<bool, fmt::Error> {
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        Ok(())
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn try_special<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        self.try_special_with(token, &Default::default())
+    }
+
+    fn try_special_with<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+        policy: &crate::TagNamePolicy,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        open_tag_name(token).map(|tag| NoParseRule::with_policy(tag, *policy).to_box())
+    }
+}
+
+/// Preformatted tag, which converts to a fenced code block. Its contents are not parsed.
+/// <br/>
+/// This matches the following BBCode tags: `["pre", "codeblock"]`
+/// # Arguments
+/// A value, such as in `[codeblock=rust]`, is used as the code block's info string (language).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PreformattedTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Markdown, CustomTy> for PreformattedTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["pre", "codeblock"]
+            .iter()
+            .any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        _: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> Result<bool, fmt::Error> {
+        out.begin_capture();
+        out.begin_verbatim();
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        out.end_verbatim();
+        let code = out.end_capture();
+        // Info strings can't contain backticks, and only the first word is the language.
+        let lang = open_token
+            .arg_list()
+            .value()
+            .and_then(|x| x.split_whitespace().next())
+            .filter(|x| !x.contains('`'))
+            .unwrap_or("");
+
+        write_code_block(&code, lang, out);
+        Ok(())
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn try_special<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        self.try_special_with(token, &Default::default())
+    }
+
+    fn try_special_with<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+        policy: &crate::TagNamePolicy,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        open_tag_name(token).map(|tag| NoParseRule::with_policy(tag, *policy).to_box())
+    }
+}
+
+/// List tag, which converts to a bullet list, or an ordered list if given a number (`[list=1]`) or written as `[ol]`.
+/// <br/>
+/// This matches the following BBCode tags: `["list", "ul", "ol"]`
+/// # Remarks
+/// Items are started by [ListItemTag]. `[*]` is usually left unclosed, so items end at the next item or the end of the
+/// list, whether or not it's configured as a void tag (see [ParserConfig::void_tags][crate::ParserConfig::void_tags]).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ListTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Markdown, CustomTy> for ListTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["list", "ul", "ol"]
+            .iter()
+            .any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> Result<bool, fmt::Error> {
+        // Other list styles, like `[list=a]`, have no Markdown equivalent and are numbered instead.
+        let start = match token.arg_list().value() {
+            Some(value) => Some(value.trim().parse().unwrap_or(1)),
+            None if token.matches_tag("ol", &Default::default()) => Some(1),
+            None => None,
+        };
+
+        out.begin_list(start);
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        out.end_list();
+        Ok(())
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn content_model(&self, _: &Token<'_, CustomTy>) -> ContentModel {
+        ContentModel::BLOCK.with_role("list")
+    }
+}
+
+/// List item tag, which starts a new item in the enclosing [ListTag]. Outside of a list it is written as text.
+/// <br/>
+/// This matches the following BBCode tags: `["*", "li"]`
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ListItemTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Markdown, CustomTy> for ListItemTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["*", "li"].iter().any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> Result<bool, fmt::Error> {
+        if !out.begin_list_item() {
+            tk_writer.write_token(token, out)?;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        _: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        // Items end when the next one starts, or the list ends.
+        Ok(())
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        self.open_tag(tk_writer, token, out).map(|_| ())
+    }
+
+    fn content_model(&self, _: &Token<'_, CustomTy>) -> ContentModel {
+        ContentModel::BLOCK.within("list")
+    }
+}
+
+/// Whether the given URL is safe to link to, being relative or using the `http`, `https` or `mailto` schemes.
+fn is_safe_url(url: &str) -> bool {
+    let scheme = url.split_once(':').map(|(scheme, _)| scheme).filter(|x| {
+        x.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+
+    match scheme {
+        Some(scheme) => ["http", "https", "mailto"]
+            .iter()
+            .any(|x| x.eq_ignore_ascii_case(scheme)),
+        None => !url.is_empty(),
+    }
+}
+
+/// Write the given URL as a link destination, using the `<...>` form if it would otherwise end early.
+/// # Remarks
+/// `&` is escaped, as entity references would otherwise be decoded after [is_safe_url] checked the scheme, such as in
+/// `javascript&colon;`.
+fn write_destination(url: &str, out: &mut MarkdownOutput) {
+    if url.contains(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '<' | '>' | '\\')) {
+        out.push_str("<");
+        out.push_str(
+            &url.replace('\\', "\\\\")
+                .replace('&', "\\&")
+                .replace('<', "\\<")
+                .replace('>', "\\>")
+                .replace('\n', " "),
+        );
+        out.push_str(">");
+    } else {
+        out.push_str(&url.replace('&', "\\&"));
+    }
+}
+
+/// Link tag, which converts to an inline link `[text](url)`, or an autolink `<url>` if given no value.
+/// <br/>
+/// This matches the following BBCode tags: `["url", "link"]`
+/// # Remarks
+/// Links with any scheme other than `http`, `https` or `mailto` are written as plain text. Without a value, the contents are not parsed.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LinkTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Markdown, CustomTy> for LinkTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        self.match_tag_with(tag, &Default::default())
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        ["url", "link"].iter().any(|x| policy.names_match(x, tag))
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> Result<bool, fmt::Error> {
+        out.begin_capture();
+        if token.arg_list().value().is_none() {
+            out.begin_verbatim();
+        }
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        match open_token.arg_list().value() {
+            Some(url) => {
+                let text = out.end_capture();
+                let url = url.trim();
+
+                if is_safe_url(url) {
+                    out.push_str("[");
+                    out.push_str(&text);
+                    out.push_str("](");
+                    write_destination(url, out);
+                    out.push_str(")");
+                } else {
+                    out.push_str(&text);
+                }
+            }
+            None => {
+                out.end_verbatim();
+                let url = out.end_capture();
+                let url = url.trim();
+
+                // Autolinks need a scheme, and can't contain whitespace or angle brackets.
+                if is_safe_url(url)
+                    && url.contains(':')
+                    && !url.contains(|c: char| c.is_whitespace() || matches!(c, '<' | '>'))
+                {
+                    out.push_str("<");
+                    out.push_str(url);
+                    out.push_str(">");
+                } else {
+                    out.push_escaped(url);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn try_special<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        self.try_special_with(token, &Default::default())
+    }
+
+    fn try_special_with<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+        policy: &crate::TagNamePolicy,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        if token.arg_list().value().is_some() {
+            return None;
+        }
+
+        open_tag_name(token).map(|tag| NoParseRule::with_policy(tag, *policy).to_box())
+    }
+}
+
+/// Image tag, which converts to an image `![alt](url)`, taking its URL from the contents. The contents are not parsed.
+/// <br/>
+/// This matches the following BBCode tags: `["img"]`
+/// # Arguments
+/// - `alt`, the image's alt text.
+///
+/// Sizes, such as `[img=100x100]`, have no Markdown equivalent and are ignored.
+/// # Remarks
+/// Images with any scheme other than `http` or `https` are written as plain text.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ImageTag<CustomTy = ()> {
+    _custom_ty: core::marker::PhantomData<CustomTy>,
+}
+
+impl<CustomTy> TagWriter<Markdown, CustomTy> for ImageTag<CustomTy>
+where
+    CustomTy: Clone + 'static,
+{
+    fn match_tag(&self, tag: &str) -> bool {
+        tag.eq_ignore_ascii_case("img")
+    }
+
+    fn match_tag_with(&self, tag: &str, policy: &crate::TagNamePolicy) -> bool {
+        policy.names_match("img", tag)
+    }
+
+    fn open_tag(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        _: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> Result<bool, fmt::Error> {
+        out.begin_capture();
+        out.begin_verbatim();
+        Ok(true)
+    }
+
+    fn close_tag<'a>(
+        &self,
+        _: &dyn TokenWriter<Markdown, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        _: &Token<'a, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        out.end_verbatim();
+        let url = out.end_capture();
+        let url = url.trim();
+
+        if !is_safe_url(url)
+            || url
+                .get(..7)
+                .map_or(false, |x| x.eq_ignore_ascii_case("mailto:"))
+        {
+            out.push_escaped(url);
+            return Ok(());
+        }
+
+        out.push_str("![");
+        out.push_escaped(open_token.arg_list().get("alt").unwrap_or(""));
+        out.push_str("](");
+        write_destination(url, out);
+        out.push_str(")");
+        Ok(())
+    }
+
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<Markdown, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut MarkdownOutput,
+    ) -> fmt::Result {
+        tk_writer.write_token(token, out)
+    }
+
+    fn try_special<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        self.try_special_with(token, &Default::default())
+    }
+
+    fn try_special_with<'a>(
+        &self,
+        token: &Token<'a, CustomTy>,
+        policy: &crate::TagNamePolicy,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        open_tag_name(token).map(|tag| NoParseRule::with_policy(tag, *policy).to_box())
+    }
+}
@@ -0,0 +1,318 @@
+//! **Simple** Markdown (CommonMark) serialization from a [BBParser]'s output.
+//!  Tags with a Markdown equivalent are converted to it, and the rest fall back to inline HTML.
+use core::fmt;
+
+use static_assertions::assert_obj_safe;
+
+use crate::{
+    render::{Backend, Renderer},
+    BBParser, Token,
+};
+
+pub use crate::render::{TagWriter, TokenWriter};
+
+/// The Markdown [Backend], writing into a [MarkdownOutput].
+/// # Remarks
+/// Token writers should write text verbatim when [MarkdownOutput::is_verbatim] is set.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Markdown;
+
+impl Backend for Markdown {
+    type Output<'w> = MarkdownOutput;
+}
+
+assert_obj_safe!(TagWriter<Markdown>);
+
+/// A dead simple Markdown writer that escapes the raw text of the token it's given.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SimpleMarkdownWriter;
+
+impl TokenWriter<Markdown, ()> for SimpleMarkdownWriter {
+    fn write_token(&self, token: &Token<'_, ()>, out: &mut MarkdownOutput) -> fmt::Result {
+        if out.is_verbatim() {
+            out.push_str(token.span);
+        } else {
+            out.push_escaped(token.span);
+        }
+        Ok(())
+    }
+}
+
+/// Markdown output buffer, which keeps track of the line prefixes (such as `> ` for quotes) needed by the blocks being written.
+/// # Remarks
+/// Everything written goes through [MarkdownOutput::push_str] or [MarkdownOutput::push_escaped],
+/// which write the current line prefixes at the start of every line. Runs of blank lines are collapsed, except in verbatim text.
+#[derive(Debug, Default)]
+pub struct MarkdownOutput {
+    buf: String,
+    prefixes: Vec<String>,
+    captures: Vec<String>,
+    lists: Vec<ListState>,
+    verbatim: usize,
+    /// Whether nothing has been written on the current line yet, so its prefixes are still pending.
+    line_start: bool,
+    /// Whether the last finished line was blank.
+    last_blank: bool,
+}
+
+/// The largest number an ordered list item may have, as CommonMark only allows up to 9 digits.
+const MAX_LIST_NUMBER: usize = 999_999_999;
+
+#[derive(Debug)]
+struct ListState {
+    next: Option<usize>,
+    item_open: bool,
+}
+
+impl MarkdownOutput {
+    fn new(capacity: usize) -> Self {
+        Self {
+            buf: String::with_capacity(capacity),
+            line_start: true,
+            last_blank: true,
+            ..Default::default()
+        }
+    }
+
+    /// Finish writing, returning the output without trailing newlines.
+    pub fn finish(mut self) -> String {
+        self.buf.truncate(self.buf.trim_end_matches('\n').len());
+        self.buf
+    }
+
+    /// Write the given string, verbatim except for line prefixes.
+    pub fn push_str(&mut self, s: &str) {
+        for (idx, line) in s.split('\n').enumerate() {
+            if idx > 0 {
+                self.newline();
+            }
+            self.write_line(line);
+        }
+    }
+
+    /// Write the given text, escaping Markdown metacharacters.
+    pub fn push_escaped(&mut self, s: &str) {
+        for (idx, line) in s.split('\n').enumerate() {
+            if idx > 0 {
+                self.newline();
+            }
+            let line_start = self.line_start && self.captures.is_empty();
+            self.write_line(&escape_line(line, line_start));
+        }
+    }
+
+    /// Whether or not nothing has been written on the current line yet.
+    pub fn is_line_start(&self) -> bool {
+        self.line_start
+    }
+
+    /// Start a new line, unless nothing has been written on the current one yet.
+    pub fn ensure_line_start(&mut self) {
+        if !self.line_start {
+            self.newline();
+        }
+    }
+
+    /// Leave a blank line before whatever is written next, as needed between Markdown blocks.
+    pub fn ensure_blank_line(&mut self) {
+        self.ensure_line_start();
+        if !self.last_blank {
+            self.newline();
+        }
+    }
+
+    /// Add a prefix to write at the start of every following line, such as `> `.
+    pub fn push_prefix(&mut self, prefix: impl Into<String>) {
+        self.prefixes.push(prefix.into());
+    }
+
+    /// Remove the last prefix added with [MarkdownOutput::push_prefix].
+    pub fn pop_prefix(&mut self) {
+        self.prefixes.pop();
+    }
+
+    /// Start capturing output, to be taken back out with [MarkdownOutput::end_capture].
+    /// # Remarks
+    /// Captured output has no line prefixes, so it can be written back out with [MarkdownOutput::push_str].
+    pub fn begin_capture(&mut self) {
+        self.captures.push(String::new());
+    }
+
+    /// Take everything written since the matching [MarkdownOutput::begin_capture] back out of the output.
+    pub fn end_capture(&mut self) -> String {
+        self.captures.pop().unwrap_or_default()
+    }
+
+    /// Start writing text verbatim, without escaping, such as within code.
+    pub fn begin_verbatim(&mut self) {
+        self.verbatim += 1;
+    }
+
+    /// Stop writing text verbatim, undoing one [MarkdownOutput::begin_verbatim].
+    pub fn end_verbatim(&mut self) {
+        self.verbatim = self.verbatim.saturating_sub(1);
+    }
+
+    /// Whether or not text should currently be written verbatim.
+    pub fn is_verbatim(&self) -> bool {
+        self.verbatim > 0
+    }
+
+    /// Start a list, numbered from the given number if ordered. Numbers are capped at 999999999, the most CommonMark allows.
+    pub fn begin_list(&mut self, start: Option<usize>) {
+        if self.lists.is_empty() {
+            self.ensure_blank_line();
+        } else {
+            self.ensure_line_start();
+        }
+
+        self.lists.push(ListState {
+            next: start.map(|x| x.min(MAX_LIST_NUMBER)),
+            item_open: false,
+        });
+    }
+
+    /// End the innermost list.
+    pub fn end_list(&mut self) {
+        self.ensure_line_start();
+
+        if let Some(list) = self.lists.pop() {
+            if list.item_open {
+                self.pop_prefix();
+            }
+        }
+
+        if self.lists.is_empty() {
+            self.ensure_blank_line();
+        }
+    }
+
+    /// Start a new item in the innermost list, ending the previous one. Returns false if there is no list to add the item to.
+    pub fn begin_list_item(&mut self) -> bool {
+        let Some(list) = self.lists.last_mut() else {
+            return false;
+        };
+
+        let marker = match list.next.as_mut() {
+            Some(n) => {
+                let marker = format!("{n}. ");
+                *n = n.saturating_add(1).min(MAX_LIST_NUMBER);
+                marker
+            }
+            None => "- ".into(),
+        };
+        let was_open = core::mem::replace(&mut list.item_open, true);
+
+        self.ensure_line_start();
+        if was_open {
+            self.pop_prefix();
+        }
+
+        self.push_str(&marker);
+        self.push_prefix(" ".repeat(marker.len()));
+        true
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+
+        if let Some(capture) = self.captures.last_mut() {
+            capture.push_str(line);
+            return;
+        }
+
+        if self.line_start {
+            self.line_start = false;
+            for p in self.prefixes.iter() {
+                self.buf.push_str(p);
+            }
+        }
+        self.buf.push_str(line);
+    }
+
+    fn newline(&mut self) {
+        if let Some(capture) = self.captures.last_mut() {
+            capture.push('\n');
+            return;
+        }
+
+        if self.line_start {
+            if self.last_blank && !self.is_verbatim() {
+                return;
+            }
+
+            // Blank lines still need the quote markers, or the quote would end, but not the trailing whitespace.
+            let prefix: String = self.prefixes.concat();
+            self.buf.push_str(prefix.trim_end());
+        }
+
+        self.last_blank = self.line_start;
+        self.line_start = true;
+        self.buf.push('\n');
+    }
+}
+
+/// Escape Markdown metacharacters in the given line, including those only special at the start of a line if `line_start` is set.
+fn escape_line(line: &str, line_start: bool) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    if line_start {
+        while let Some(c) = chars.next_if(|c| c.is_whitespace()) {
+            out.push(c);
+        }
+
+        if let Some('#' | '-' | '+' | '=') = chars.peek() {
+            out.push('\\');
+        } else {
+            // Ordered list markers, like `1.` or `1)`.
+            let mut digits = false;
+            while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                out.push(c);
+                digits = true;
+            }
+            if digits && matches!(chars.peek(), Some('.' | ')')) {
+                out.push('\\');
+            }
+        }
+    }
+
+    for c in chars {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~' | '&'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+/// Serializes a BBCode parse (from [BBParser]) to Markdown using the registered tags and writer.
+pub type MarkdownSerializer<Writer = SimpleMarkdownWriter, CustomTy = ()> =
+    Renderer<Markdown, Writer, CustomTy>;
+
+impl<Writer, CustomTy> Renderer<Markdown, Writer, CustomTy>
+where
+    CustomTy: Clone + 'static,
+    Writer: TokenWriter<Markdown, CustomTy>,
+{
+    /// Serialize the given BBCode 'document' out to Markdown, using the provided writer and tags.
+    /// # Remarks
+    /// See [Renderer::render] for details.
+    pub fn serialize(&mut self, parser: BBParser<'_, CustomTy>) -> String {
+        let mut out = MarkdownOutput::new(parser.remaining().len());
+        self.render(parser, &mut out)
+            .expect("a writer returned an error when writing Markdown");
+        out.finish()
+    }
+}
+
+pub mod builtins;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,140 @@
+use crate::{markdown::builtins, BBParser, ParserConfig};
+
+use super::{MarkdownSerializer, SimpleMarkdownWriter};
+
+fn serialize(input: &str) -> String {
+    let mut serializer =
+        MarkdownSerializer::<SimpleMarkdownWriter>::with_tags(builtins::all_tags());
+    serializer.serialize(BBParser::new(input))
+}
+
+const SIMPLE: &str = "[b]Foo[/b] [i]bar[/i] [s]baz[/s]";
+
+#[test]
+pub fn simple() {
+    assert_eq!(serialize(SIMPLE), "**Foo** *bar* ~~baz~~");
+}
+
+const ESCAPED: &str = "[b]*not* <b>bold</b>[/b] [unknown]\n# 1. 2) - a_b\n12. x";
+
+#[test]
+pub fn escaped() {
+    assert_eq!(
+        serialize(ESCAPED),
+        "**\\*not\\* \\<b\\>bold\\</b\\>** \\[unknown\\]\n\\# 1. 2) - a\\_b\n12\\. x"
+    );
+}
+
+const HTML_FALLBACK: &str = "[u]under[/u] H[sub]2[/sub]O x[sup]2[/sup]";
+
+#[test]
+pub fn html_fallback() {
+    assert_eq!(
+        serialize(HTML_FALLBACK),
+        "<u>under</u> H<sub>2</sub>O x<sup>2</sup>"
+    );
+}
+
+const BLOCKS: &str = "Intro[title]Title[/title]\nText\n[center]Centered[/center]Outro";
+
+#[test]
+pub fn blocks() {
+    assert_eq!(
+        serialize(BLOCKS),
+        "Intro\n\n# Title\n\nText\n\n<div align=\"center\">\n\nCentered\n\n</div>\n\nOutro"
+    );
+}
+
+const QUOTES: &str = "[quote]Outer\n\n[quote]Inner[/quote]\nAfter[/quote]Done";
+
+#[test]
+pub fn quotes() {
+    assert_eq!(
+        serialize(QUOTES),
+        "> Outer\n>\n> > Inner\n>\n> After\n\nDone"
+    );
+}
+
+const CODE: &str =
+    "Run [code]a[b]*c*[/b]`d`[/code] or\n[code]\nfn main() {\n\n}\n[/code][pre=rust]```[/pre]";
+
+#[test]
+pub fn code() {
+    assert_eq!(
+        serialize(CODE),
+        "Run `` a[b]*c*[/b]`d` `` or\n\n```\nfn main() {\n\n}\n```\n\n````rust\n```\n````"
+    );
+}
+
+const LIST: &str = "[list]\n[*]One\n[*]Two[list=1][*]A[*]B[/list]\n[/list]\n[*]Not an item";
+
+#[test]
+pub fn list() {
+    let parser = BBParser::with_config(
+        LIST,
+        ParserConfig {
+            void_tags: ParserConfig::COMMON_VOID_TAGS,
+            ..Default::default()
+        },
+    );
+    let mut serializer =
+        MarkdownSerializer::<SimpleMarkdownWriter>::with_tags(builtins::all_tags());
+
+    assert_eq!(
+        serializer.serialize(parser),
+        "- One\n- Two\n  1. A\n  2. B\n\n\\[\\*\\]Not an item"
+    );
+}
+
+const LIST_ITEMS_OPEN: &str = "[list]\n[*]One\n[*]Two\n[/list]";
+
+#[test]
+pub fn list_items_open() {
+    assert_eq!(serialize(LIST_ITEMS_OPEN), "- One\n- Two");
+}
+
+const LIST_START: &str = "[list=18446744073709551615][*]a[*]b[/list]";
+
+#[test]
+pub fn list_start() {
+    let parser = BBParser::with_config(
+        LIST_START,
+        ParserConfig {
+            void_tags: ParserConfig::COMMON_VOID_TAGS,
+            ..Default::default()
+        },
+    );
+    let mut serializer =
+        MarkdownSerializer::<SimpleMarkdownWriter>::with_tags(builtins::all_tags());
+
+    // CommonMark list numbers have at most 9 digits.
+    assert_eq!(serializer.serialize(parser), "999999999. a\n999999999. b");
+}
+
+const LINKS: &str = "[url]https://example.com/a_b[/url] [url=https://example.com/(x)]the [b]site[/b][/url] [url=javascript:alert(1)]bad[/url] [img alt=Cat]cat.png[/img]";
+
+#[test]
+pub fn links() {
+    assert_eq!(
+        serialize(LINKS),
+        "<https://example.com/a_b> [the **site**](<https://example.com/(x)>) bad ![Cat](cat.png)"
+    );
+}
+
+const LINK_ENTITIES: &str = "[url=javascript&colon;alert(1)]x[/url] [url=https://example.com/?a=1&b=2]y[/url] [img]java&#115;cript&#58;x[/img]";
+
+#[test]
+pub fn link_entities() {
+    // Entity references mustn't be decoded after the scheme was checked.
+    assert_eq!(
+        serialize(LINK_ENTITIES),
+        "[x](<javascript\\&colon;alert(1)>) [y](https://example.com/?a=1\\&b=2) ![](java\\&#115;cript\\&#58;x)"
+    );
+}
+
+const DANGLING: &str = "[quote][b]This is a test!";
+
+#[test]
+pub fn dangling() {
+    assert_eq!(serialize(DANGLING), "> **This is a test!**");
+}
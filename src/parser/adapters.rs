@@ -0,0 +1,319 @@
+use alloc::vec::Vec;
+
+use crate::{BBTag, TagNamePolicy, Token, TokenKind};
+
+/// What to do with a tag, as decided by a [TagFilter].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagAction {
+    /// Keep the tag as-is.
+    Keep,
+    /// Rewrite the tag, and its pair, as text.
+    Textualize,
+    /// Remove the tag and its pair, keeping everything between them.
+    Unwrap,
+    /// Remove the tag, its pair, and everything between them.
+    Remove,
+}
+
+/// Decides what happens to each tag passing through [FilterTags].
+pub trait TagFilter<'a, CustomTy = ()>
+where
+    CustomTy: Clone,
+{
+    /// Decide the action for the given open, standalone or unpaired close tag.
+    /// # Remarks
+    /// This is never called for paired close tags, which always share the action of their open tag, nor for anything within a removed tag.
+    fn action(&mut self, token: &Token<'a, CustomTy>) -> TagAction;
+}
+
+impl<'a, CustomTy, F> TagFilter<'a, CustomTy> for F
+where
+    CustomTy: Clone,
+    F: FnMut(&Token<'a, CustomTy>) -> TagAction,
+{
+    fn action(&mut self, token: &Token<'a, CustomTy>) -> TagAction {
+        self(token)
+    }
+}
+
+/// Applies the same action to every tag.
+impl<'a, CustomTy> TagFilter<'a, CustomTy> for TagAction
+where
+    CustomTy: Clone,
+{
+    fn action(&mut self, _: &Token<'a, CustomTy>) -> TagAction {
+        *self
+    }
+}
+
+/// Keeps only the given tags, unwrapping all others. See [TokenStreamExt::drop_unknown_tags].
+#[derive(Debug, Clone, Copy)]
+pub struct KnownTags<'k> {
+    known: &'k [&'k str],
+    policy: TagNamePolicy,
+}
+
+impl<'k> KnownTags<'k> {
+    /// Keep the given tags, comparing names with the default [TagNamePolicy].
+    pub fn new(known: &'k [&'k str]) -> Self {
+        Self::with_policy(known, Default::default())
+    }
+
+    /// Keep the given tags, comparing names with the given policy.
+    pub fn with_policy(known: &'k [&'k str], policy: TagNamePolicy) -> Self {
+        Self { known, policy }
+    }
+}
+
+impl<'a, 'k, CustomTy> TagFilter<'a, CustomTy> for KnownTags<'k>
+where
+    CustomTy: Clone,
+{
+    fn action(&mut self, token: &Token<'a, CustomTy>) -> TagAction {
+        if self
+            .known
+            .iter()
+            .any(|x| token.matches_tag(x, &self.policy))
+        {
+            TagAction::Keep
+        } else {
+            TagAction::Unwrap
+        }
+    }
+}
+
+/// Unwraps all tags matching a predicate. See [TokenStreamExt::reject_tags].
+#[derive(Debug, Clone, Copy)]
+pub struct RejectTags<P>(P);
+
+impl<'a, CustomTy, P> TagFilter<'a, CustomTy> for RejectTags<P>
+where
+    CustomTy: Clone,
+    P: FnMut(&Token<'a, CustomTy>) -> bool,
+{
+    fn action(&mut self, token: &Token<'a, CustomTy>) -> TagAction {
+        if (self.0)(token) {
+            TagAction::Unwrap
+        } else {
+            TagAction::Keep
+        }
+    }
+}
+
+/// Applies a [TagFilter] to a stream of tokens one at a time, keeping track of open tags so that tag pairs share the same action.
+/// # Remarks
+/// Open and close tags are paired back up by name, the same way [BBParser][crate::BBParser] pairs them, so that a close tag always
+/// shares the fate of its open tag. This is the state behind [FilterTags], for use when the stream can't be wrapped directly.
+pub struct TagFilterState<'a, F> {
+    filter: F,
+    policy: TagNamePolicy,
+    stack: Vec<(&'a str, TagAction)>,
+    removing: usize,
+}
+
+impl<'a, F> TagFilterState<'a, F> {
+    /// Construct a new state around the given filter.
+    pub fn new(filter: F) -> Self {
+        Self {
+            filter,
+            policy: Default::default(),
+            stack: Vec::new(),
+            removing: 0,
+        }
+    }
+
+    /// Pair tags up using the given name policy, instead of the default.
+    /// This should match the [ParserConfig::tag_names][crate::ParserConfig::tag_names] of the parser producing the tokens.
+    pub fn with_policy(mut self, policy: TagNamePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// All tags currently open, in order of opening, alongside the action taken for them.
+    pub fn open_tags(&self) -> &[(&'a str, TagAction)] {
+        &self.stack
+    }
+
+    /// Decide the action for the given token, which must be the next token in the stream.
+    pub fn action<CustomTy>(&mut self, token: &Token<'a, CustomTy>) -> TagAction
+    where
+        CustomTy: Clone,
+        F: TagFilter<'a, CustomTy>,
+    {
+        match token.kind {
+            TokenKind::OpenBBTag(BBTag { tag, .. }) => {
+                let action = if self.removing > 0 {
+                    TagAction::Remove
+                } else {
+                    self.filter.action(token)
+                };
+
+                if action == TagAction::Remove {
+                    self.removing += 1;
+                }
+
+                self.stack.push((tag, action));
+                action
+            }
+            // Paired close tags share the fate of their open tag, even when closed out of order inside a removal.
+            TokenKind::CloseBBTag(BBTag { tag, .. }, Some(_)) => match self.pop_matching(tag) {
+                Some(action) => action,
+                None if self.removing > 0 => TagAction::Remove,
+                None => self.filter.action(token),
+            },
+            TokenKind::CloseBBTag(..) | TokenKind::StandaloneBBTag(_) => {
+                if self.removing > 0 {
+                    TagAction::Remove
+                } else {
+                    self.filter.action(token)
+                }
+            }
+            _ if self.removing > 0 => TagAction::Remove,
+            _ => TagAction::Keep,
+        }
+    }
+
+    fn pop_matching(&mut self, tag: &str) -> Option<TagAction> {
+        let idx = self
+            .stack
+            .iter()
+            .rposition(|(x, _)| self.policy.names_match(x, tag))?;
+        let (_, action) = self.stack.remove(idx);
+
+        if action == TagAction::Remove {
+            self.removing -= 1;
+        }
+
+        Some(action)
+    }
+}
+
+/// Token stream adapter applying a [TagFilter] to every tag, see [TokenStreamExt::filter_tags].
+/// # Remarks
+/// Close tags always share the fate of their open tag, see [TagFilterState].
+/// Spans, starts and pairing indices of the tokens that are kept are left untouched.
+pub struct FilterTags<'a, I, F> {
+    inner: I,
+    state: TagFilterState<'a, F>,
+}
+
+impl<'a, I, F> FilterTags<'a, I, F> {
+    /// Pair tags up using the given name policy, instead of the default.
+    /// This should match the [ParserConfig::tag_names][crate::ParserConfig::tag_names] of the parser producing the tokens.
+    pub fn with_policy(mut self, policy: TagNamePolicy) -> Self {
+        self.state.policy = policy;
+        self
+    }
+}
+
+impl<'a, I, F, CustomTy> Iterator for FilterTags<'a, I, F>
+where
+    CustomTy: Clone,
+    I: Iterator<Item = Token<'a, CustomTy>>,
+    F: TagFilter<'a, CustomTy>,
+{
+    type Item = Token<'a, CustomTy>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut token = self.inner.next()?;
+
+            match self.state.action(&token) {
+                TagAction::Keep => return Some(token),
+                TagAction::Textualize => {
+                    token.rewrite_as_text();
+                    return Some(token);
+                }
+                TagAction::Unwrap | TagAction::Remove => continue,
+            }
+        }
+    }
+}
+
+/// Token stream adapter renaming tags, see [TokenStreamExt::rename_tags].
+pub struct RenameTags<I, F> {
+    inner: I,
+    rename: F,
+}
+
+impl<'a, I, F, CustomTy> Iterator for RenameTags<I, F>
+where
+    CustomTy: Clone,
+    I: Iterator<Item = Token<'a, CustomTy>>,
+    F: FnMut(&'a str) -> &'a str,
+{
+    type Item = Token<'a, CustomTy>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut token = self.inner.next()?;
+
+        match token.kind {
+            TokenKind::OpenBBTag(ref mut t)
+            | TokenKind::CloseBBTag(ref mut t, _)
+            | TokenKind::StandaloneBBTag(ref mut t) => t.tag = (self.rename)(t.tag),
+            _ => {}
+        }
+
+        Some(token)
+    }
+}
+
+/// Composable adapters over any stream of tokens, such as a [BBParser][crate::BBParser].
+pub trait TokenStreamExt<'a, CustomTy = ()>: Iterator<Item = Token<'a, CustomTy>> + Sized
+where
+    CustomTy: Clone,
+{
+    /// Keep, textualize, unwrap or remove each tag as decided by the given filter, keeping tag pairs consistent.
+    fn filter_tags<F>(self, filter: F) -> FilterTags<'a, Self, F>
+    where
+        F: TagFilter<'a, CustomTy>,
+    {
+        FilterTags {
+            inner: self,
+            state: TagFilterState::new(filter),
+        }
+    }
+
+    /// Remove all tags, keeping text and custom tokens.
+    fn strip_tags(self) -> FilterTags<'a, Self, TagAction> {
+        self.filter_tags(TagAction::Unwrap)
+    }
+
+    /// Keep only plain text tokens.
+    fn text_only(self) -> core::iter::Filter<Self, fn(&Token<'a, CustomTy>) -> bool> {
+        self.filter(Token::is_text as _)
+    }
+
+    /// Unwrap all tags not in the given list, keeping their contents.
+    fn drop_unknown_tags<'k>(self, known: &'k [&'k str]) -> FilterTags<'a, Self, KnownTags<'k>> {
+        self.filter_tags(KnownTags::new(known))
+    }
+
+    /// Unwrap all tags matching the given predicate, keeping their contents.
+    fn reject_tags<P>(self, predicate: P) -> FilterTags<'a, Self, RejectTags<P>>
+    where
+        P: FnMut(&Token<'a, CustomTy>) -> bool,
+    {
+        self.filter_tags(RejectTags(predicate))
+    }
+
+    /// Rename every tag with the given function, such as to map aliases (`bold`) onto a single name (`b`).
+    /// # Remarks
+    /// Only the tag name is changed, spans still contain the original text.
+    fn rename_tags<F>(self, rename: F) -> RenameTags<Self, F>
+    where
+        F: FnMut(&'a str) -> &'a str,
+    {
+        RenameTags {
+            inner: self,
+            rename,
+        }
+    }
+}
+
+impl<'a, CustomTy, I> TokenStreamExt<'a, CustomTy> for I
+where
+    CustomTy: Clone,
+    I: Iterator<Item = Token<'a, CustomTy>>,
+{
+}
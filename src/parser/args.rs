@@ -0,0 +1,134 @@
+/// A single argument of a tag, such as the `Name` in `[quote=Name]` or the `post=5` in `[quote post=5]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TagArg<'a> {
+    /// The key of the argument, if it has one.
+    pub key: Option<&'a str>,
+    /// The value of the argument, with any surrounding quotes removed.
+    pub value: &'a str,
+}
+
+/// Iterator over the arguments of a tag, see [Token::arg_list][crate::Token::arg_list].
+/// # Syntax
+/// - A leading `=value` is the primary value, and has no key. If unquoted, it runs until the next ` key=` or the end of the arguments,
+///   so `[quote=John Smith post=5]` has the primary value `John Smith`.
+/// - `key=value` pairs follow, separated by whitespace.
+/// - Words without a `=` are values without a key.
+///
+/// Values may be quoted with `"` or `'`, in which case they run until the matching quote.
+#[derive(Debug, Clone)]
+pub struct TagArgs<'a> {
+    rest: &'a str,
+    first: bool,
+}
+
+impl<'a> TagArgs<'a> {
+    /// Construct an iterator over the given raw tag arguments, as found in [BBTag::args][crate::BBTag::args].
+    pub fn new(args: &'a str) -> Self {
+        Self {
+            rest: args,
+            first: true,
+        }
+    }
+
+    /// The first argument without a key, usually the primary value in `[tag=value]`.
+    pub fn value(&self) -> Option<&'a str> {
+        self.clone().find(|x| x.key.is_none()).map(|x| x.value)
+    }
+
+    /// The value of the first argument with the given key, compared ignoring ASCII case.
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.clone()
+            .find(|x| x.key.map_or(false, |k| k.eq_ignore_ascii_case(key)))
+            .map(|x| x.value)
+    }
+
+    /// Read a possibly quoted value from the start of `rest`, ending at whitespace if unquoted.
+    fn read_value(&mut self) -> &'a str {
+        if let Some((value, rest)) = Self::split_quoted(self.rest) {
+            self.rest = rest;
+            return value;
+        }
+
+        let end = self
+            .rest
+            .find(char::is_whitespace)
+            .unwrap_or(self.rest.len());
+        let (value, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        value
+    }
+
+    fn split_quoted(s: &'a str) -> Option<(&'a str, &'a str)> {
+        let quote = s.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        let end = s[1..].find(quote)? + 1;
+        Some((&s[1..end], &s[(end + 1)..]))
+    }
+
+    /// Find where an unquoted primary value ends, which is at the first whitespace followed by `key=`.
+    fn primary_end(s: &str) -> usize {
+        for (idx, c) in s.char_indices() {
+            if !c.is_whitespace() {
+                continue;
+            }
+
+            let after = s[idx..].trim_start();
+            let key_len = after
+                .find(|c: char| c.is_whitespace() || c == '=')
+                .unwrap_or(after.len());
+
+            if key_len > 0 && after[key_len..].starts_with('=') {
+                return idx;
+            }
+        }
+
+        s.len()
+    }
+}
+
+impl<'a> Iterator for TagArgs<'a> {
+    type Item = TagArg<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rest = self.rest.trim_start();
+
+        if core::mem::take(&mut self.first) {
+            if let Some(rest) = self.rest.strip_prefix('=') {
+                self.rest = rest.trim_start();
+
+                let value = if let Some((value, rest)) = Self::split_quoted(self.rest) {
+                    self.rest = rest;
+                    value
+                } else {
+                    let (value, rest) = self.rest.split_at(Self::primary_end(self.rest));
+                    self.rest = rest;
+                    value.trim_end()
+                };
+
+                return Some(TagArg { key: None, value });
+            }
+        }
+
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let key_len = self
+            .rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(self.rest.len());
+
+        if key_len > 0 && self.rest[key_len..].starts_with('=') {
+            let key = &self.rest[..key_len];
+            self.rest = &self.rest[(key_len + 1)..];
+            Some(TagArg {
+                key: Some(key),
+                value: self.read_value(),
+            })
+        } else {
+            Some(TagArg {
+                key: None,
+                value: self.read_value(),
+            })
+        }
+    }
+}
@@ -7,11 +7,17 @@ use static_assertions::{assert_impl_all, assert_not_impl_all};
 use bitflags::bitflags;
 
 /// Provides configuration information for [BBParser], including enabled feature flags.
+#[derive(Clone, Copy)]
 pub struct ParserConfig<'a> {
     /// Feature flags for this configuration.
     pub feature_flags: ParserFeature,
     /// What bracket characters should be supported as tags.
     pub brackets: &'a [(&'a str, &'a str)],
+    /// Tags that never have a closing tag, such as `br` or `hr`.
+    /// These are always emitted as [TokenKind::StandaloneBBTag], with or without a trailing `/`.
+    pub void_tags: &'a [&'a str],
+    /// How tag names are recognized and compared.
+    pub tag_names: TagNamePolicy,
 }
 
 impl<'a> Default for ParserConfig<'a> {
@@ -19,10 +25,105 @@ impl<'a> Default for ParserConfig<'a> {
         Self {
             feature_flags: ParserFeature::NONE,
             brackets: &[("[", "]")],
+            void_tags: &[],
+            tag_names: Default::default(),
         }
     }
 }
 
+impl<'a> ParserConfig<'a> {
+    /// A set of commonly used void tags, for use with [ParserConfig::void_tags].
+    pub const COMMON_VOID_TAGS: &'static [&'static str] = &["br", "hr", "*"];
+
+    /// Whether or not the given tag name is a void tag in this configuration.
+    pub fn is_void_tag(&self, tag: &str) -> bool {
+        self.void_tags
+            .iter()
+            .any(|x| self.tag_names.names_match(x, tag))
+    }
+}
+
+/// Describes which tag names the parser accepts, and how they are compared.
+#[derive(Debug, Clone, Copy)]
+pub struct TagNamePolicy {
+    /// Characters permitted within a tag name. Tags with names containing anything else are treated as text.
+    pub permitted_chars: fn(char) -> bool,
+    /// Whether or not tag names are compared case sensitively.
+    pub case_sensitive: bool,
+    /// Whether or not case insensitive comparisons use Unicode full case folding, so `ß` matches `SS` and `ς` matches `Σ`,
+    /// instead of only ignoring ASCII case.
+    /// Has no effect if [TagNamePolicy::case_sensitive] is set.
+    pub unicode_case_folding: bool,
+    /// Whether or not a tag's name is split from its arguments on any whitespace, instead of just `' '`.
+    pub split_on_any_whitespace: bool,
+}
+
+impl Default for TagNamePolicy {
+    fn default() -> Self {
+        Self {
+            permitted_chars: Self::any_char,
+            case_sensitive: false,
+            unicode_case_folding: false,
+            split_on_any_whitespace: false,
+        }
+    }
+}
+
+impl TagNamePolicy {
+    /// Permits any character in tag names, the default.
+    pub fn any_char(_: char) -> bool {
+        true
+    }
+
+    /// Permits ASCII alphanumerics, `_`, `-` and `*` in tag names.
+    pub fn ascii_word_char(c: char) -> bool {
+        c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '*')
+    }
+
+    /// Permits Unicode alphanumerics, `_`, `-` and `*` in tag names.
+    pub fn unicode_word_char(c: char) -> bool {
+        c.is_alphanumeric() || matches!(c, '_' | '-' | '*')
+    }
+
+    /// Whether or not the two given tag names are the same tag under this policy.
+    pub fn names_match(&self, a: &str, b: &str) -> bool {
+        if self.case_sensitive {
+            a == b
+        } else if self.unicode_case_folding {
+            Self::case_fold(a).eq(Self::case_fold(b))
+        } else {
+            a.eq_ignore_ascii_case(b)
+        }
+    }
+
+    /// Fold the case of the given name, such that names differing only in case fold to the same characters.
+    /// # Remarks
+    /// Uppercasing expands characters like `ß` to `SS` and unifies `ς` with `σ`, and lowercasing after folds the rest. This is
+    /// done twice, so characters only reached by lowercasing, like `ß` from `ẞ`, are expanded too.
+    fn case_fold(name: &str) -> impl Iterator<Item = char> + '_ {
+        name.chars()
+            .flat_map(char::to_uppercase)
+            .flat_map(char::to_lowercase)
+            .flat_map(char::to_uppercase)
+            .flat_map(char::to_lowercase)
+    }
+
+    /// Whether or not the given tag name only contains permitted characters.
+    pub fn is_permitted(&self, name: &str) -> bool {
+        name.chars().all(self.permitted_chars)
+    }
+
+    /// Whether or not the given character separates a tag's name from its arguments.
+    pub fn is_separator(&self, c: char) -> bool {
+        c == '='
+            || if self.split_on_any_whitespace {
+                c.is_whitespace()
+            } else {
+                c == ' '
+            }
+    }
+}
+
 bitflags! {
     /// Represents a set of flags.
     #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -35,6 +136,17 @@ bitflags! {
         /// Textualize close tags with no corresponding open tag, instead of preserving the token.
         const UNMATCHED_CLOSE_AS_TEXT = 1 << 2;
 
+        /// Treat an empty close tag (`[/]`) as closing the innermost open tag.
+        /// The token is rewritten into a normal [TokenKind::CloseBBTag] with the real tag name and pairing index.
+        /// Requires `track_open_tags`, does nothing otherwise.
+        const GENERIC_CLOSE = 1 << 3;
+
+        /// Merge adjacent text, including tags rewritten as text, into a single [TokenKind::Text] token with a contiguous span.
+        /// # Remarks
+        /// The parser has to look one token past the end of each run of text to find where it ends, so that token
+        /// is parsed (and tracked in [BBParser::open_tags]) before the text is returned.
+        const COALESCE_TEXT = 1 << 4;
+
         /// All compatibility features in v1.0.0 and earlier.
         const V1 = Self::POP_UNORDERED.bits() | Self::UNMATCHED_CLOSE_AS_TEXT.bits();
 
@@ -77,6 +189,8 @@ where
     closed_tags: Vec<Token<'a, CustomTy>>,
     #[cfg(feature = "parser_rules")]
     rule_stack: Vec<Box<dyn rules::ParserRuleObj<'a, CustomTy> + Send + 'a>>,
+    /// A token parsed ahead of time while coalescing text, see [ParserFeature::COALESCE_TEXT].
+    pending: Option<Token<'a, CustomTy>>,
     _custom_ty: PhantomData<CustomTy>,
 }
 
@@ -113,6 +227,7 @@ impl<'a> BBParser<'a> {
             closed_tags: vec![],
             #[cfg(feature = "parser_rules")]
             rule_stack: vec![],
+            pending: None,
             _custom_ty: PhantomData,
         }
     }
@@ -135,6 +250,7 @@ impl<'a> BBParser<'a> {
             closed_tags: vec![],
             #[cfg(feature = "parser_rules")]
             rule_stack: vec![],
+            pending: None,
             _custom_ty: PhantomData,
         }
     }
@@ -144,6 +260,11 @@ impl<'a, CustomTy> BBParser<'a, CustomTy>
 where
     CustomTy: Clone,
 {
+    /// Returns the entire input text, including anything already parsed.
+    pub fn input(&self) -> &'a str {
+        self.input
+    }
+
     /// Returns all input text left to parse
     pub fn remaining(&self) -> &str {
         &self.input[self.loc..]
@@ -154,7 +275,7 @@ where
         &self.input[(self.loc + after)..]
     }
 
-    pub fn config(&self) -> &ParserConfig<'_> {
+    pub fn config(&self) -> &ParserConfig<'a> {
         &self.config
     }
 
@@ -208,6 +329,36 @@ where
     type Item = Token<'a, CustomTy>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let mut token = self.pending.take().or_else(|| self.next_token())?;
+
+        if !token.is_text()
+            || !self
+                .config
+                .feature_flags
+                .contains(ParserFeature::COALESCE_TEXT)
+        {
+            return Some(token);
+        }
+
+        while let Some(next) = self.next_token() {
+            if next.is_text() && next.start == token.start + token.span.len() {
+                token.span = &self.input[token.start..(next.start + next.span.len())];
+            } else {
+                self.pending = Some(next);
+                break;
+            }
+        }
+
+        Some(token)
+    }
+}
+
+impl<'a, CustomTy> BBParser<'a, CustomTy>
+where
+    CustomTy: Clone,
+{
+    /// Parses the next token from the input, ignoring [BBParser::pending].
+    fn next_token(&mut self) -> Option<Token<'a, CustomTy>> {
         fn to_token_kind<'a, CustomTy>(tag: &'a str, args: &'a str) -> TokenKind<'a, CustomTy> {
             if tag.starts_with('/') {
                 // End block.
@@ -290,22 +441,40 @@ where
                     let span =
                         &self.input[self.loc..(self.loc + tag_end + opener.len() + closer.len())];
 
-                    if let Some(arg_idx) = tag_contents.find(['=', ' ']) {
+                    let kind = if let Some(arg_idx) =
+                        tag_contents.find(|c| self.config.tag_names.is_separator(c))
+                    {
                         let (tag, args) = tag_contents.split_at(arg_idx);
-                        // Inlined from Self::remaining() due to the borrowchecker not being able to see per-field borrows.
-
-                        break 'tk Token::<'a, CustomTy> {
-                            span,
-                            start: self.loc,
-                            kind: to_token_kind(tag, args),
-                        };
+                        to_token_kind(tag, args)
                     } else {
-                        break 'tk Token::<'a, CustomTy> {
-                            span,
-                            start: self.loc,
-                            kind: to_token_kind_single(tag_contents),
-                        };
+                        to_token_kind_single(tag_contents)
+                    };
+
+                    let name = match &kind {
+                        TokenKind::OpenBBTag(BBTag { tag, .. })
+                        | TokenKind::CloseBBTag(BBTag { tag, .. }, _)
+                        | TokenKind::StandaloneBBTag(BBTag { tag, .. }) => *tag,
+                        _ => unreachable!(),
+                    };
+
+                    if !self.config.tag_names.is_permitted(name) {
+                        break 'no_match;
                     }
+
+                    // Void tags are standalone regardless of how they were written.
+                    let kind = match kind {
+                        TokenKind::OpenBBTag(tag) if self.config.is_void_tag(tag.tag) => {
+                            TokenKind::StandaloneBBTag(tag)
+                        }
+                        kind => kind,
+                    };
+
+                    // Inlined from Self::remaining() due to the borrowchecker not being able to see per-field borrows.
+                    break 'tk Token::<'a, CustomTy> {
+                        span,
+                        start: self.loc,
+                        kind,
+                    };
                 }
             }
 
@@ -361,11 +530,25 @@ where
                 self.open_tags.push(token.clone());
             }
 
+            if let TokenKind::CloseBBTag(BBTag { tag: "", args }, _) = token.kind {
+                if self
+                    .config
+                    .feature_flags
+                    .contains(ParserFeature::GENERIC_CLOSE)
+                {
+                    if let Some(TokenKind::OpenBBTag(BBTag { tag, .. })) =
+                        self.open_tags.last().map(|x| &x.kind)
+                    {
+                        token.kind = TokenKind::CloseBBTag(BBTag { tag, args }, None);
+                    }
+                }
+            }
+
             if let TokenKind::CloseBBTag(BBTag { tag: removee, .. }, _) = token.kind {
                 let to_remove: Option<usize> = 'blk: {
                     for (idx, tag) in self.open_tags.iter().enumerate().rev() {
                         if let TokenKind::OpenBBTag(ref t) = tag.kind {
-                            if t.tag.eq_ignore_ascii_case(removee) {
+                            if self.config.tag_names.names_match(t.tag, removee) {
                                 break 'blk Some(idx);
                             } else if !self
                                 .config
@@ -436,6 +619,18 @@ where
         }
     }
 
+    /// The arguments for this tag split into individual values and `key=value` pairs, see [TagArgs] for the syntax.
+    /// # Remarks
+    /// This is empty for tokens which aren't tags.
+    pub fn arg_list(&self) -> TagArgs<'a> {
+        match self.kind {
+            TokenKind::OpenBBTag(BBTag { args, .. })
+            | TokenKind::CloseBBTag(BBTag { args, .. }, _)
+            | TokenKind::StandaloneBBTag(BBTag { args, .. }) => TagArgs::new(args),
+            _ => TagArgs::new(""),
+        }
+    }
+
     /// The tag name for this tag.
     pub fn tag_name(&self) -> Option<&str> {
         match self.kind {
@@ -446,15 +641,20 @@ where
         }
     }
 
+    /// Whether or not this token is a tag (of any kind) with the given name, compared using the given policy.
+    pub fn matches_tag(&self, tag_name: &str, policy: &TagNamePolicy) -> bool {
+        self.tag_name()
+            .map(|tag| policy.names_match(tag, tag_name))
+            .unwrap_or(false)
+    }
+
     /// Whether or not this tag is an open tag of the given type.
     /// # Remarks
     /// This **ignores** the arguments of the tag, use [Token::is_open_argless] to ensure they're empty.
+    /// Tag names are compared using the default [TagNamePolicy], use [Token::matches_tag] for any other policy.
     pub fn is_open(&self, tag_name: &str) -> bool {
-        if let TokenKind::OpenBBTag(BBTag { tag, .. }) = self.kind {
-            tag.eq_ignore_ascii_case(tag_name)
-        } else {
-            false
-        }
+        matches!(self.kind, TokenKind::OpenBBTag(_))
+            && self.matches_tag(tag_name, &TagNamePolicy::default())
     }
 
     /// Whether or not this tag is an open tag of the given type, without arguments.
@@ -465,12 +665,10 @@ where
     /// Whether or not this tag is a close tag of the given type.
     /// # Remarks
     /// This **ignores** the arguments of the tag, use [Token::is_close_argless] to ensure they're empty.
+    /// Tag names are compared using the default [TagNamePolicy], use [Token::matches_tag] for any other policy.
     pub fn is_close(&self, tag_name: &str) -> bool {
-        if let TokenKind::CloseBBTag(BBTag { tag, .. }, ..) = self.kind {
-            tag.eq_ignore_ascii_case(tag_name)
-        } else {
-            false
-        }
+        matches!(self.kind, TokenKind::CloseBBTag(..))
+            && self.matches_tag(tag_name, &TagNamePolicy::default())
     }
 
     /// Whether or not this tag is a close tag of the given type, without arguments.
@@ -481,12 +679,10 @@ where
     /// Whether or not this tag is a standalone tag of the given type.
     /// # Remarks
     /// This **ignores** the arguments of the tag, use [Token::is_standalone_argless] to ensure they're empty.
+    /// Tag names are compared using the default [TagNamePolicy], use [Token::matches_tag] for any other policy.
     pub fn is_standalone(&self, tag_name: &str) -> bool {
-        if let TokenKind::StandaloneBBTag(BBTag { tag, .. }) = self.kind {
-            tag.eq_ignore_ascii_case(tag_name)
-        } else {
-            false
-        }
+        matches!(self.kind, TokenKind::StandaloneBBTag(_))
+            && self.matches_tag(tag_name, &TagNamePolicy::default())
     }
 
     /// Whether or not this tag is a standalone tag of the given type, without arguments.
@@ -563,6 +759,13 @@ pub enum TokenKind<'a, CustomTy = ()> {
     Custom(CustomTy),
 }
 
+mod args;
+pub use args::{TagArg, TagArgs};
+
+#[cfg(feature = "alloc")]
+/// Composable adapters over token streams, such as stripping, filtering or renaming tags.
+pub mod adapters;
+
 #[cfg(feature = "parser_rules")]
 /// Parser rules, which can be pushed into a [BBParser] mid-iteration to change how parsing behaves.
 pub mod rules;
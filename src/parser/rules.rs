@@ -1,3 +1,4 @@
+use alloc::boxed::Box;
 use core::marker::PhantomData;
 
 use crate::{BBParser, Token};
@@ -62,6 +63,7 @@ where
 }
 
 /// Internal wrapper over parse rules to make them object safe, this is the struct containing the user provided rule.
+#[allow(clippy::needless_maybe_sized)]
 pub(super) struct ParserRuleImpl<'a, Rule, CustomTy>
 where
     Rule: ParserRule<'a, CustomTy> + ?Sized,
@@ -74,6 +76,7 @@ where
 
 //SAFETY: This is safe, we only implement Send when Rule is send, and CustomTy is never stored anywhere within ParserRuleImpl itself.
 //SAFETY: If there was a way to have PhantomData always implement Send+Sync (as we never store CustomTy ourselves, and if the Rule does their type reflects that), then this would be unnecessary.
+#[allow(clippy::needless_maybe_sized)]
 unsafe impl<'a, Rule, CustomTy> Send for ParserRuleImpl<'a, Rule, CustomTy>
 where
     Rule: ParserRule<'a, CustomTy> + ?Sized + Send,
@@ -109,33 +112,41 @@ where
 pub mod builtin {
     use core::marker::PhantomData;
 
-    use crate::{parser::BBTag, Token, TokenKind};
+    use crate::{parser::BBTag, TagNamePolicy, Token, TokenKind};
 
     use super::{ParserRule, ParserRuleAction};
 
     pub struct NoParseRule<'a, CustomTy = ()> {
-        _custom_ty: PhantomData<CustomTy>,
+        // CustomTy is never stored, so the rule is Send regardless of it.
+        _custom_ty: PhantomData<fn() -> CustomTy>,
         tag_name: &'a str,
+        policy: TagNamePolicy,
     }
 
     impl<'a, CustomTy> NoParseRule<'a, CustomTy> {
         pub fn new(tag_name: &'a str) -> Self {
+            Self::with_policy(tag_name, Default::default())
+        }
+
+        /// Constructs a rule that compares its closing tag name using the given policy.
+        pub fn with_policy(tag_name: &'a str, policy: TagNamePolicy) -> Self {
             Self {
                 _custom_ty: PhantomData,
                 tag_name,
+                policy,
             }
         }
     }
 
     impl<'a, 'rule_life: 'a, CustomTy> ParserRule<'a, CustomTy> for NoParseRule<'rule_life, CustomTy>
     where
-        CustomTy: Clone + Send + 'a,
+        CustomTy: Clone + 'a,
     {
         const ACTION: ParserRuleAction = ParserRuleAction::NoParse;
 
         fn transform_token(&self, next: &mut Token<'_, CustomTy>) -> bool {
             if let TokenKind::CloseBBTag(BBTag { tag, .. }, ..) = next.kind {
-                tag.eq_ignore_ascii_case(self.tag_name)
+                self.policy.names_match(tag, self.tag_name)
             } else {
                 false
             }
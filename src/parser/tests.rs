@@ -187,6 +187,7 @@ pub fn misskeyish() {
         crate::ParserConfig {
             feature_flags: ParserFeature::V1,
             brackets: &[("[", "]"), ("<", ">"), ("$[", "]")],
+            ..Default::default()
         },
     );
     let tokens: alloc::vec::Vec<Token<'static, ()>> = parser.collect();
@@ -202,8 +203,269 @@ pub fn no_mismatched_tags() {
         crate::ParserConfig {
             feature_flags: ParserFeature::V1,
             brackets: &[("[", "]"), ("<", ">"), ("$[", "]")],
+            ..Default::default()
         },
     );
     let tokens: alloc::vec::Vec<Token<'static, ()>> = parser.collect();
     assert!(tokens.iter().all(|x| x.is_text()));
 }
+
+const VOID_TAGS: &str = "[br][BR /][hr/][*]item[br=clear]";
+
+#[test]
+pub fn void_tags() {
+    let parser = BBParser::with_config(
+        VOID_TAGS,
+        crate::ParserConfig {
+            void_tags: crate::ParserConfig::COMMON_VOID_TAGS,
+            ..Default::default()
+        },
+    );
+    let tokens: alloc::vec::Vec<Token<'static, ()>> = parser.collect();
+    assert!(tokens[0].is_standalone_argless("br"));
+    assert!(tokens[1].is_standalone_argless("br"));
+    assert!(tokens[2].is_standalone_argless("hr"));
+    assert!(tokens[3].is_standalone_argless("*"));
+    assert!(tokens[4].is_text());
+    assert!(tokens[5].is_standalone("br"));
+    assert_eq!(tokens[5].args(), Some("=clear"));
+}
+
+#[cfg(feature = "track_open_tags")]
+#[test]
+pub fn void_tags_never_open() {
+    let mut parser = BBParser::with_config(
+        VOID_TAGS,
+        crate::ParserConfig {
+            void_tags: crate::ParserConfig::COMMON_VOID_TAGS,
+            ..Default::default()
+        },
+    );
+    for _ in parser.by_ref() {}
+    assert!(parser.open_tags().is_empty());
+}
+
+const GENERIC_CLOSE: &str = "[b]bold [i]both[/] bold[/] [/]";
+
+#[cfg(feature = "track_open_tags")]
+#[test]
+pub fn generic_close() {
+    let parser = BBParser::with_config(
+        GENERIC_CLOSE,
+        crate::ParserConfig {
+            feature_flags: ParserFeature::GENERIC_CLOSE | ParserFeature::UNMATCHED_CLOSE_AS_TEXT,
+            ..Default::default()
+        },
+    );
+    let tokens: alloc::vec::Vec<Token<'static, ()>> = parser.collect();
+    assert!(tokens[4].is_close_argless("i"));
+    assert!(matches!(tokens[4].kind, TokenKind::CloseBBTag(_, Some(0))));
+    assert_eq!(tokens[4].span, "[/]");
+    assert!(tokens[6].is_close_argless("b"));
+    assert!(matches!(tokens[6].kind, TokenKind::CloseBBTag(_, Some(1))));
+    // Nothing is left open, so the last one is unmatched.
+    assert!(tokens[8].is_text());
+}
+
+const TAG_NAME_POLICY: &str = "[Straße]x[/STRASSE][/straße][b\tfoo]y[b\u{a0}bar][<3]";
+
+#[cfg(feature = "track_open_tags")]
+#[test]
+pub fn tag_name_policy() {
+    use crate::TagNamePolicy;
+
+    let parser = BBParser::with_config(
+        TAG_NAME_POLICY,
+        crate::ParserConfig {
+            tag_names: TagNamePolicy {
+                permitted_chars: TagNamePolicy::unicode_word_char,
+                unicode_case_folding: true,
+                split_on_any_whitespace: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let tokens: alloc::vec::Vec<Token<'static, ()>> = parser.collect();
+    assert!(tokens[0].is_open("Straße"));
+    // Full case folding turns `ß` into `ss`, so this closes it, leaving nothing for the next.
+    assert!(matches!(tokens[2].kind, TokenKind::CloseBBTag(_, Some(0))));
+    assert!(matches!(tokens[3].kind, TokenKind::CloseBBTag(_, None)));
+    assert!(tokens[4].is_open("b"));
+    assert_eq!(tokens[4].args(), Some("foo"));
+    assert!(tokens[6].is_open("b"));
+    assert!(tokens[7].is_text());
+}
+
+#[test]
+pub fn unicode_case_folding() {
+    use crate::TagNamePolicy;
+
+    let policy = TagNamePolicy {
+        unicode_case_folding: true,
+        ..Default::default()
+    };
+    assert!(policy.names_match("straße", "STRASSE"));
+    assert!(policy.names_match("STRAẞE", "strasse"));
+    assert!(policy.names_match("ΟΔΟΣ", "οδος"));
+    assert!(policy.names_match("οδος", "οδοσ"));
+    assert!(policy.names_match("Ǆ", "ǆ"));
+    assert!(!policy.names_match("straße", "strase"));
+    assert!(!TagNamePolicy::default().names_match("straße", "STRASSE"));
+}
+
+#[cfg(feature = "track_open_tags")]
+#[test]
+pub fn case_sensitive_tag_names() {
+    use crate::TagNamePolicy;
+
+    let parser = BBParser::with_config(
+        "[b]x[/B][/b]",
+        crate::ParserConfig {
+            tag_names: TagNamePolicy {
+                case_sensitive: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let tokens: alloc::vec::Vec<Token<'static, ()>> = parser.collect();
+    assert!(matches!(tokens[2].kind, TokenKind::CloseBBTag(_, None)));
+    assert!(matches!(tokens[3].kind, TokenKind::CloseBBTag(_, Some(0))));
+    assert!(tokens[3].matches_tag("b", &TagNamePolicy::default()));
+}
+
+const FRAGMENTED_TEXT: &str = "a[b c[d [/e] f[g]h";
+
+#[cfg(feature = "track_open_tags")]
+#[test]
+pub fn coalesce_text() {
+    let parser = BBParser::with_config(
+        FRAGMENTED_TEXT,
+        crate::ParserConfig {
+            feature_flags: ParserFeature::COALESCE_TEXT | ParserFeature::UNMATCHED_CLOSE_AS_TEXT,
+            ..Default::default()
+        },
+    );
+    let tokens: alloc::vec::Vec<Token<'static, ()>> = parser.collect();
+    assert_eq!(tokens.len(), 3);
+    assert!(tokens[0].is_text());
+    assert_eq!(tokens[0].span, "a[b c[d [/e] f");
+    assert_eq!(tokens[0].start, 0);
+    assert!(tokens[1].is_open("g"));
+    assert!(tokens[2].is_text());
+    assert_eq!(tokens[2].span, "h");
+}
+
+const ADAPTERS: &str = "[bold]a[/bold][url=x]b[spoiler]c[/url]d[/spoiler][br/]e";
+
+#[cfg(feature = "track_open_tags")]
+#[test]
+pub fn adapters() {
+    use crate::adapters::{TagAction, TokenStreamExt};
+    use alloc::{string::String, vec::Vec};
+
+    let config = || crate::ParserConfig {
+        feature_flags: ParserFeature::POP_UNORDERED,
+        ..Default::default()
+    };
+
+    let text: String = BBParser::with_config(ADAPTERS, config())
+        .strip_tags()
+        .map(|x| x.span)
+        .collect();
+    assert_eq!(text, "abcde");
+
+    let text: String = BBParser::with_config(ADAPTERS, config())
+        .text_only()
+        .map(|x| x.span)
+        .collect();
+    assert_eq!(text, "abcde");
+
+    let tokens: Vec<Token<'static, ()>> = BBParser::with_config(ADAPTERS, config())
+        .rename_tags(|x| {
+            if x.eq_ignore_ascii_case("bold") {
+                "b"
+            } else {
+                x
+            }
+        })
+        .drop_unknown_tags(&["b", "br"])
+        .collect();
+    assert!(tokens[0].is_open("b"));
+    assert_eq!(tokens[0].span, "[bold]");
+    assert!(matches!(tokens[2].kind, TokenKind::CloseBBTag(_, Some(0))));
+    assert!(tokens[2].is_close("b"));
+    assert!(tokens[6].is_standalone("br"));
+
+    // Removing the spoiler must not take the out of order url close with it.
+    let spans: Vec<&str> = BBParser::with_config(ADAPTERS, config())
+        .filter_tags(|x: &Token<'static, ()>| {
+            if x.is_open("spoiler") {
+                TagAction::Remove
+            } else if x.is_open("url") {
+                TagAction::Textualize
+            } else {
+                TagAction::Keep
+            }
+        })
+        .map(|x| x.span)
+        .collect();
+    assert_eq!(
+        spans,
+        ["[bold]", "a", "[/bold]", "[url=x]", "b", "[/url]", "[br/]", "e"]
+    );
+
+    let spans: Vec<&str> = BBParser::with_config(ADAPTERS, config())
+        .reject_tags(|x| x.args().is_some())
+        .map(|x| x.span)
+        .collect();
+    assert_eq!(
+        spans,
+        [
+            "[bold]",
+            "a",
+            "[/bold]",
+            "b",
+            "[spoiler]",
+            "c",
+            "d",
+            "[/spoiler]",
+            "[br/]",
+            "e"
+        ]
+    );
+}
+
+const ARGS: &str = "[quote=John Smith post=5 time=\"12:00 UTC\"][img 'a b' width=5][size= 3 ]";
+
+#[test]
+pub fn arg_list() {
+    use crate::{TagArg, TagArgs};
+
+    let tokens: alloc::vec::Vec<Token<'static, ()>> = BBParser::new(ARGS).collect();
+
+    let args = tokens[0].arg_list();
+    assert_eq!(args.value(), Some("John Smith"));
+    assert_eq!(args.get("POST"), Some("5"));
+    assert_eq!(args.get("time"), Some("12:00 UTC"));
+    assert_eq!(args.count(), 3);
+
+    let args: alloc::vec::Vec<TagArg<'static>> = tokens[1].arg_list().collect();
+    assert_eq!(
+        args,
+        [
+            TagArg {
+                key: None,
+                value: "a b"
+            },
+            TagArg {
+                key: Some("width"),
+                value: "5"
+            }
+        ]
+    );
+
+    assert_eq!(tokens[2].arg_list().value(), Some("3"));
+    assert!(TagArgs::new("").next().is_none());
+}
@@ -0,0 +1,1131 @@
+//! Format-agnostic rendering of a [BBParser]'s output, shared by the [html][crate::html] and [markdown][crate::markdown] serializers.
+//!  A [Backend] names an output format, and [Renderer] drives the tag and token writers registered for it.
+use core::fmt;
+use std::{collections::HashMap, io};
+
+use crate::{rules::ParserRuleObjBox, BBParser, BBTag, TagNamePolicy, Token, TokenKind};
+
+/// An output format, such as HTML, which tag and token writers are implemented for.
+pub trait Backend {
+    /// The output being written to, such as a [fmt::Write] sink, which may borrow for `'w`.
+    type Output<'w>: ?Sized
+    where
+        Self: 'w;
+}
+
+/// Where a tag's output may appear and what it may contain, used by [Renderer] to keep the output validly nested.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ContentModel {
+    /// Whether the tag is a block, such as a quote, rather than inline, such as bold text.
+    pub block: bool,
+    /// Whether the tag may contain blocks.
+    pub contains_blocks: bool,
+    /// Whether newlines within the tag are kept as they are, such as in preformatted text.
+    pub verbatim: bool,
+    /// The role this tag fills for the tags directly within it, such as `list`. Such a tag only holds its own items, so
+    /// anything else directly within it is moved into the next item, or after the tag if there isn't one, and whitespace
+    /// is dropped. Blocks are written out as text there.
+    pub role: Option<&'static str>,
+    /// The role of the tag which this tag must be directly within, such as `list` for a list item. Elsewhere the tag is
+    /// written out as text.
+    /// # Remarks
+    /// The standalone form of such a tag, such as `[*]`, opens it until the next tag needing the same parent, or until the
+    /// parent is closed.
+    pub parent: Option<&'static str>,
+}
+
+impl ContentModel {
+    /// An inline tag which may only contain inline tags, such as bold text.
+    pub const INLINE: Self = Self {
+        block: false,
+        contains_blocks: false,
+        verbatim: false,
+        role: None,
+        parent: None,
+    };
+
+    /// A block tag which may contain anything, such as a quote.
+    pub const BLOCK: Self = Self {
+        block: true,
+        contains_blocks: true,
+        verbatim: false,
+        role: None,
+        parent: None,
+    };
+
+    /// A block tag which may only contain inline tags, such as a heading.
+    pub const TEXT_BLOCK: Self = Self {
+        block: true,
+        contains_blocks: false,
+        verbatim: false,
+        role: None,
+        parent: None,
+    };
+
+    /// An inline tag which may contain anything, placing no restrictions on nesting.
+    pub const ANY: Self = Self {
+        block: false,
+        contains_blocks: true,
+        verbatim: false,
+        role: None,
+        parent: None,
+    };
+
+    /// An inline tag which may only contain text, kept as it is, such as inline code.
+    pub const VERBATIM_INLINE: Self = Self {
+        block: false,
+        contains_blocks: false,
+        verbatim: true,
+        role: None,
+        parent: None,
+    };
+
+    /// A block tag which may only contain text, kept as it is, such as preformatted text.
+    pub const VERBATIM_BLOCK: Self = Self {
+        block: true,
+        contains_blocks: false,
+        verbatim: true,
+        role: None,
+        parent: None,
+    };
+
+    /// This model, filling the given role for the tags directly within it.
+    pub const fn with_role(self, role: &'static str) -> Self {
+        Self {
+            role: Some(role),
+            ..self
+        }
+    }
+
+    /// This model, required to be directly within a tag filling the given role.
+    pub const fn within(self, parent: &'static str) -> Self {
+        Self {
+            parent: Some(parent),
+            ..self
+        }
+    }
+
+    /// Whether a tag with this model may directly contain one with the given model.
+    pub fn may_contain(&self, other: &ContentModel) -> bool {
+        !other.block || self.contains_blocks
+    }
+}
+
+/// The primary trait for converting BBCode tags to a backend's output format.
+pub trait TagWriter<B, CustomTy = ()>
+where
+    B: Backend,
+    CustomTy: Clone + 'static,
+{
+    /// Whether or not this generator is the correct generator for the given tag.
+    /// # Remarks
+    /// The implementation reserves the right to cache the result of this function on a per-instance basis, this function MUST
+    /// always return the same output for each possible input.
+    fn match_tag(&self, tag: &str) -> bool;
+
+    /// Whether or not this generator is the correct generator for the given tag, comparing names with the given policy.
+    /// # Remarks
+    /// The default implementation ignores the policy and defers to [TagWriter::match_tag].
+    fn match_tag_with(&self, tag: &str, _policy: &TagNamePolicy) -> bool {
+        self.match_tag(tag)
+    }
+
+    /// Produce an open tag for the given token, writing it to the given output.
+    /// Returns whether an element was opened, rather than the tag written out as text, such as for arguments it doesn't
+    /// support. A tag written out as text is never closed or opened again, and its close tag is written out as text too.
+    /// # Remarks
+    /// The output provided may already have contents, an implementation must not overwrite prior contents.
+    /// Errors from the output must be returned, so they reach the caller.
+    fn open_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<B, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut B::Output<'_>,
+    ) -> Result<bool, fmt::Error>;
+
+    /// Produce a close tag for the given token, writing it to the given output.
+    /// # Remarks
+    /// The output provided may already have contents, an implementation must not overwrite prior contents.
+    fn close_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<B, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        close_token: &Token<'a, CustomTy>,
+        out: &mut B::Output<'_>,
+    ) -> fmt::Result;
+
+    /// Produce a standalone tag for the given token, writing it to the given output.
+    /// # Remarks
+    /// The output provided may already have contents, an implementation must not overwrite prior contents.
+    /// Standalone tags MUST NOT rely on other tags around them.
+    fn standalone_tag(
+        &self,
+        tk_writer: &dyn TokenWriter<B, CustomTy>,
+        token: &Token<'_, CustomTy>,
+        out: &mut B::Output<'_>,
+    ) -> fmt::Result;
+
+    /// Whether the given open tag takes its contents as a value, such as the URL in `[url]https://example.com[/url]`.
+    /// # Remarks
+    /// If so, the contents are read up to the matching close tag, and the whole tag is written by [TagWriter::contents_tag]
+    /// instead of [TagWriter::open_tag] and [TagWriter::close_tag]. Without a matching close tag, the open tag is written out
+    /// as text instead, rather than taking the rest of the document. Any rule from [TagWriter::try_special] is introduced first,
+    /// which should usually stop the contents from being parsed, such as a [NoParseRule][crate::rules::builtin::NoParseRule].
+    fn takes_contents(&self, _token: &Token<'_, CustomTy>) -> bool {
+        false
+    }
+
+    /// Produce the whole tag for the given open token and its raw contents, for tags which [take their contents][TagWriter::takes_contents].
+    /// The close token is `None` if the tag was never closed.
+    /// # Remarks
+    /// The output provided may already have contents, an implementation must not overwrite prior contents.
+    /// The default implementation writes the tag and its contents as text.
+    fn contents_tag<'a>(
+        &self,
+        tk_writer: &dyn TokenWriter<B, CustomTy>,
+        open_token: &Token<'a, CustomTy>,
+        contents: &Token<'a, CustomTy>,
+        close_token: Option<&Token<'a, CustomTy>>,
+        out: &mut B::Output<'_>,
+    ) -> fmt::Result {
+        tk_writer.write_token(open_token, out)?;
+        tk_writer.write_token(contents, out)?;
+        match close_token {
+            Some(close_token) => tk_writer.write_token(close_token, out),
+            None => Ok(()),
+        }
+    }
+
+    /// Where the output for the given tag may appear and what it may contain.
+    /// # Remarks
+    /// The default implementation returns [ContentModel::ANY], so the tag is never moved or degraded to text.
+    fn content_model(&self, _token: &Token<'_, CustomTy>) -> ContentModel {
+        ContentModel::ANY
+    }
+
+    /// Try to produce a new parser rule to introduce from the given token.
+    fn try_special<'a>(
+        &self,
+        _token: &'_ Token<'a, CustomTy>,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        None
+    }
+
+    /// Try to produce a new parser rule to introduce from the given token, comparing names with the given policy.
+    /// # Remarks
+    /// The default implementation ignores the policy and defers to [TagWriter::try_special].
+    fn try_special_with<'a>(
+        &self,
+        token: &'_ Token<'a, CustomTy>,
+        _policy: &TagNamePolicy,
+    ) -> Option<ParserRuleObjBox<'a, CustomTy>> {
+        self.try_special(token)
+    }
+}
+
+/// Implements writing non-tag tokens.
+pub trait TokenWriter<B, CustomTy = ()>
+where
+    B: Backend,
+    CustomTy: Clone,
+{
+    /// Write the given token to the given output.
+    /// # Remarks
+    /// The token is not guaranteed to only be text or custom tags, if a tag fails to match any tag writer it will end up here.
+    /// The output provided may already have contents, an implementation must not overwrite prior contents.
+    /// Errors from the output must be returned, so they reach the caller.
+    fn write_token(&self, token: &Token<'_, CustomTy>, out: &mut B::Output<'_>) -> fmt::Result;
+}
+
+/// How newlines in text are written by a [Renderer], see [Renderer::set_newlines].
+pub struct Newlines<B, CustomTy = ()>
+where
+    B: Backend,
+    CustomTy: Clone + 'static,
+{
+    /// Writes a single newline, as a standalone tag.
+    pub line_break: Box<dyn TagWriter<B, CustomTy>>,
+    /// Wraps paragraphs, which are split by blank lines. Without one, every newline is written as a line break.
+    pub paragraph: Option<Box<dyn TagWriter<B, CustomTy>>>,
+}
+
+/// Renders a BBCode parse (from [BBParser]) to a backend's output format using the registered tags and writer.
+pub struct Renderer<B, Writer, CustomTy = ()>
+where
+    B: Backend,
+    CustomTy: Clone + 'static,
+    Writer: TokenWriter<B, CustomTy>,
+{
+    writer: Writer,
+    tag_impls: Vec<Box<dyn TagWriter<B, CustomTy>>>,
+    tag_cache: HashMap<String, usize>,
+    newlines: Option<Newlines<B, CustomTy>>,
+}
+
+impl<B, Writer> Renderer<B, Writer>
+where
+    B: Backend,
+    Writer: TokenWriter<B, ()> + Default,
+{
+    /// Construct a new renderer with no tags.
+    pub fn empty() -> Self {
+        Self::with_tags(vec![])
+    }
+
+    /// Construct a new renderer with the given tags.
+    pub fn with_tags(tags: Vec<Box<dyn TagWriter<B, ()>>>) -> Self {
+        Self::custom_with_tags(tags)
+    }
+}
+
+impl<B, Writer, CustomTy> Renderer<B, Writer, CustomTy>
+where
+    B: Backend,
+    CustomTy: Clone + 'static,
+    Writer: TokenWriter<B, CustomTy> + Default,
+{
+    /// Construct a new renderer with the given tags.
+    pub fn custom_with_tags(tags: Vec<Box<dyn TagWriter<B, CustomTy>>>) -> Self {
+        Self::custom(tags, Writer::default())
+    }
+}
+
+impl<B, Writer, CustomTy> Renderer<B, Writer, CustomTy>
+where
+    B: Backend,
+    CustomTy: Clone + 'static,
+    Writer: TokenWriter<B, CustomTy>,
+{
+    /// Construct a new renderer with the given tags and writer.
+    pub fn custom(tag_impls: Vec<Box<dyn TagWriter<B, CustomTy>>>, writer: Writer) -> Self {
+        Self {
+            tag_impls,
+            writer,
+            tag_cache: Default::default(),
+            newlines: None,
+        }
+    }
+
+    /// Set how newlines in text are written, or `None` to write them as they are, which is the default.
+    /// # Remarks
+    /// Newlines within [verbatim][ContentModel::verbatim] tags are always written as they are. Newlines directly before
+    /// or after a block are dropped, as the block already starts a new line.
+    pub fn set_newlines(&mut self, newlines: Option<Newlines<B, CustomTy>>) {
+        self.newlines = newlines;
+    }
+
+    /// Render the given BBCode 'document' to the given output, using the provided writer and tags.
+    /// # Remarks
+    /// Every tag opened by a writer is closed exactly once, in reverse order of opening. Tags still open at the end of the
+    /// document are closed as if by a tag with no arguments.
+    ///
+    /// Out of order closes, from [ParserFeature::POP_UNORDERED][crate::ParserFeature::POP_UNORDERED], are repaired so the output
+    /// stays well nested: the tags opened since are closed first, and opened again before any following content.
+    ///
+    /// Tags are also kept to their [ContentModel]s. Inline tags around a block that they may not contain are closed before
+    /// it, and opened again after it. They're only opened once there's content for them, so a block straight after one
+    /// doesn't leave it empty. A block that still can't be placed, such as a heading within a heading, is written
+    /// out as text instead, along with its close. Tags with a [parent][ContentModel::parent], such as list items, are written
+    /// out as text unless directly within it, and anything else directly within a tag with a [role][ContentModel::role] is
+    /// moved into its next item.
+    /// # Errors
+    /// Returns the first error produced by a writer, at which point rendering stops.
+    pub fn render(
+        &mut self,
+        mut parser: BBParser<'_, CustomTy>,
+        out: &mut B::Output<'_>,
+    ) -> fmt::Result {
+        let this = &*self;
+        let policy = parser.config().tag_names;
+        let mut nesting = Nesting {
+            open: Vec::new(),
+            pending: Vec::new(),
+            degraded: Vec::new(),
+            deferred: Vec::new(),
+            unclosed: Vec::new(),
+            newlines: 0,
+            after_block: false,
+        };
+
+        'outer: while let Some(tk) = parser.next() {
+            match tk.kind {
+                TokenKind::OpenBBTag(_)
+                | TokenKind::CloseBBTag(_, Some(_))
+                | TokenKind::StandaloneBBTag(_) => {
+                    let Some(writer) =
+                        this.get_writer_for_tag_with(tk.tag_name().unwrap(), &policy)
+                    else {
+                        this.write_content(&mut nesting, &tk, out, &policy)?;
+                        continue 'outer;
+                    };
+                    let model = writer.content_model(&tk);
+
+                    if matches!(tk.kind, TokenKind::OpenBBTag(_))
+                        && writer.takes_contents(&tk)
+                        && !this.has_close(&mut nesting, writer, &parser, &tk, &policy)
+                    {
+                        // Without a close, the rest of the document isn't taken as its contents.
+                        this.write_content(&mut nesting, &tk, out, &policy)?;
+                        continue 'outer;
+                    }
+
+                    // The rule changes how the rest is parsed, so it applies even if the tag ends up written as text.
+                    if let Some(r) = writer.try_special_with(&tk, &policy) {
+                        parser.push_rule_obj(r);
+                    }
+
+                    match tk.kind {
+                        TokenKind::OpenBBTag(_) if writer.takes_contents(&tk) => {
+                            let mut close = None;
+                            while let Some(next) = parser.next() {
+                                if let TokenKind::CloseBBTag(_, Some(idx)) = next.kind {
+                                    if parser.closed_tags()[idx].start == tk.start {
+                                        close = Some(next);
+                                        break;
+                                    }
+                                }
+                            }
+                            let input = parser.input();
+                            let from = tk.start + tk.span.len();
+                            let to = close.as_ref().map_or(input.len(), |x| x.start);
+                            let contents = Token {
+                                span: &input[from..to],
+                                start: from,
+                                kind: TokenKind::Text,
+                            };
+
+                            this.before_content(&mut nesting, model.block, out, &policy)?;
+                            let Some(suspended) = this.make_way(&mut nesting, &model, out)? else {
+                                this.write_content(&mut nesting, &tk, out, &policy)?;
+                                this.write_content(&mut nesting, &contents, out, &policy)?;
+                                if let Some(close) = &close {
+                                    this.write_content(&mut nesting, close, out, &policy)?;
+                                }
+                                continue 'outer;
+                            };
+                            nesting.pending.splice(0..0, suspended);
+
+                            writer.contents_tag(
+                                &this.writer,
+                                &tk,
+                                &contents,
+                                close.as_ref(),
+                                out,
+                            )?;
+                            nesting.after_block |= model.block;
+                            continue 'outer;
+                        }
+                        TokenKind::OpenBBTag(_)
+                            if !model.block
+                                && !model.contains_blocks
+                                && !model.verbatim
+                                && model.parent.is_none() =>
+                        {
+                            // Opened once there's content for it, so a block straight after doesn't leave it empty.
+                            nesting.pending.push(OpenTag {
+                                token: tk.clone(),
+                                writer,
+                                model,
+                                paragraph: false,
+                                implicit: false,
+                                unwritten: true,
+                                suspended: Vec::new(),
+                            });
+                        }
+                        TokenKind::OpenBBTag(_) => {
+                            this.before_content(&mut nesting, model.block, out, &policy)?;
+                            if !this.open_nested(&mut nesting, tk.clone(), false, out, &policy)? {
+                                continue 'outer;
+                            }
+
+                            if model.block && model.parent.is_none() {
+                                // Inline tags waiting to be opened again are kept until after the block.
+                                let pending = core::mem::take(&mut nesting.pending);
+                                nesting.open.last_mut().unwrap().suspended.extend(pending);
+                                nesting.after_block = true;
+                            }
+                        }
+                        TokenKind::CloseBBTag(_, Some(other)) => {
+                            let open_tk = &parser.closed_tags()[other];
+
+                            if nesting
+                                .pending
+                                .iter()
+                                .any(|x| x.unwritten && x.token.start == open_tk.start)
+                            {
+                                // Closed before any content, so it's written empty, as nothing came between.
+                                this.before_content(&mut nesting, false, out, &policy)?;
+                            }
+
+                            if let Some(idx) =
+                                nesting.degraded.iter().position(|x| *x == open_tk.start)
+                            {
+                                nesting.degraded.remove(idx);
+                                this.write_content(&mut nesting, &tk, out, &policy)?;
+                                continue 'outer;
+                            }
+
+                            let Some(idx) = nesting
+                                .open
+                                .iter()
+                                .rposition(|x| !x.paragraph && x.token.start == open_tk.start)
+                            else {
+                                // Already closed, such as to make way for a block, so it just isn't opened again.
+                                nesting.pending.retain(|x| x.token.start != open_tk.start);
+                                for x in nesting.open.iter_mut() {
+                                    x.suspended.retain(|x| x.token.start != open_tk.start);
+                                }
+                                continue 'outer;
+                            };
+
+                            this.close_nested(&mut nesting, idx, &tk, out)?;
+                        }
+                        TokenKind::StandaloneBBTag(_) if model.parent.is_some() => {
+                            this.before_content(&mut nesting, model.block, out, &policy)?;
+                            if !this.open_nested(&mut nesting, tk.clone(), true, out, &policy)? {
+                                continue 'outer;
+                            }
+                            nesting.after_block |= model.block;
+                        }
+                        TokenKind::StandaloneBBTag(_) => {
+                            this.before_content(&mut nesting, model.block, out, &policy)?;
+
+                            let Some(suspended) = this.make_way(&mut nesting, &model, out)? else {
+                                this.write_content(&mut nesting, &tk, out, &policy)?;
+                                continue 'outer;
+                            };
+                            nesting.pending.splice(0..0, suspended);
+
+                            writer.standalone_tag(&this.writer, &tk, out)?;
+                            nesting.after_block |= model.block;
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                TokenKind::CloseBBTag(_, None) => {
+                    match this.unmatched_close(&nesting, &tk, &policy) {
+                        Some(idx) => this.close_nested(&mut nesting, idx, &tk, out)?,
+                        None => this.write_content(&mut nesting, &tk, out, &policy)?,
+                    }
+                }
+                TokenKind::Text
+                    if this.newlines.is_some()
+                        && !nesting.open.iter().any(|x| x.model.verbatim) =>
+                {
+                    this.write_text(&mut nesting, &tk, out, &policy)?
+                }
+                _ => this.write_content(&mut nesting, &tk, out, &policy)?,
+            }
+        }
+
+        if nesting.pending.iter().any(|x| x.unwritten) {
+            this.before_content(&mut nesting, false, out, &policy)?;
+        }
+        for x in core::mem::take(&mut nesting.open).iter().rev() {
+            // Handle any dangling tags, those without a writer were already written out as text.
+            this.close_open(x, out)?;
+        }
+
+        if !nesting.deferred.is_empty() {
+            // Content with no item left to move into goes after the tag instead.
+            nesting.pending.clear();
+            this.before_content(&mut nesting, false, out, &policy)?;
+            for x in nesting.open.iter().rev() {
+                this.close_open(x, out)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the given token as it is, such as text or a tag written out as text, once content may be placed.
+    /// Directly within a tag with a [role][ContentModel::role], it's kept for the next item instead, or dropped if only
+    /// whitespace.
+    fn write_content<'r, 'a>(
+        &'r self,
+        nesting: &mut Nesting<'r, 'a, B, CustomTy>,
+        token: &Token<'a, CustomTy>,
+        out: &mut B::Output<'_>,
+        policy: &TagNamePolicy,
+    ) -> fmt::Result {
+        if Self::within_role(nesting) {
+            if !matches!(token.kind, TokenKind::Text) || !token.span.trim().is_empty() {
+                nesting.deferred.push(token.clone());
+            }
+            return Ok(());
+        }
+
+        self.before_content(nesting, false, out, policy)?;
+        self.writer.write_token(token, out)
+    }
+
+    /// Whether the given open tag, which takes its contents, has a matching close tag, parsing ahead as its writer would.
+    fn has_close<'r, 'a>(
+        &'r self,
+        nesting: &mut Nesting<'r, 'a, B, CustomTy>,
+        writer: &dyn TagWriter<B, CustomTy>,
+        parser: &BBParser<'a, CustomTy>,
+        token: &Token<'a, CustomTy>,
+        policy: &TagNamePolicy,
+    ) -> bool {
+        let TokenKind::OpenBBTag(BBTag { tag: name, .. }) = token.kind else {
+            return false;
+        };
+        if nesting.unclosed.iter().any(|x| policy.names_match(x, name)) {
+            return false;
+        }
+
+        let mut ahead =
+            BBParser::with_config_and_custom(&parser.input()[token.start..], *parser.config());
+        ahead.next();
+        if let Some(r) = writer.try_special_with(token, policy) {
+            ahead.push_rule_obj(r);
+        }
+
+        while let Some(next) = ahead.next() {
+            if let TokenKind::CloseBBTag(_, Some(idx)) = next.kind {
+                if ahead.closed_tags()[idx].start == 0 {
+                    return true;
+                }
+            }
+        }
+
+        // Anything later with the same name can't be closed either, as what's left of the document only gets shorter.
+        nesting.unclosed.push(name);
+        false
+    }
+
+    /// Whether content placed now would be directly within a tag with a [role][ContentModel::role].
+    fn within_role(nesting: &Nesting<'_, '_, B, CustomTy>) -> bool {
+        nesting
+            .open
+            .last()
+            .map_or(false, |x| x.model.role.is_some())
+    }
+
+    /// Write the given text token, following the newline policy.
+    fn write_text<'r, 'a>(
+        &'r self,
+        nesting: &mut Nesting<'r, 'a, B, CustomTy>,
+        token: &Token<'a, CustomTy>,
+        out: &mut B::Output<'_>,
+        policy: &TagNamePolicy,
+    ) -> fmt::Result {
+        let mut offset = 0;
+
+        for (idx, line) in token.span.split('\n').enumerate() {
+            if idx > 0 && !core::mem::take(&mut nesting.after_block) {
+                nesting.newlines += 1;
+            }
+
+            let start = offset;
+            offset += line.len() + 1;
+            let line = line.strip_suffix('\r').unwrap_or(line);
+
+            if line.trim().is_empty() {
+                // Whitespace between words is kept, but not around newlines or before a paragraph starts.
+                let is_last = offset > token.span.len();
+                if line.is_empty()
+                    || !is_last
+                    || nesting.newlines > 0
+                    || self.paragraph_starts_here(nesting)
+                {
+                    continue;
+                }
+            }
+
+            let line = Token {
+                span: line,
+                start: token.start + start,
+                kind: TokenKind::Text,
+            };
+            self.write_content(nesting, &line, out, policy)?;
+        }
+
+        Ok(())
+    }
+
+    /// Prepare for content to be written: writing any newlines seen before it, and opening any tags and paragraph
+    /// it belongs in. Newlines are dropped before a block instead, which itself starts a new line.
+    fn before_content<'r, 'a>(
+        &'r self,
+        nesting: &mut Nesting<'r, 'a, B, CustomTy>,
+        block: bool,
+        out: &mut B::Output<'_>,
+        policy: &TagNamePolicy,
+    ) -> fmt::Result {
+        let newlines = core::mem::take(&mut nesting.newlines);
+        nesting.after_block = false;
+
+        if block {
+            // Blocks are opened again, but inline tags wait until after the block, and aren't written if closed before.
+            let (blocks, mut inline) = core::mem::take(&mut nesting.pending)
+                .into_iter()
+                .partition::<Vec<_>, _>(|x| x.model.block);
+            for x in inline.iter_mut() {
+                x.unwritten = false;
+            }
+            nesting.pending = inline;
+            for x in blocks {
+                self.open_nested(nesting, x.token, false, out, policy)?;
+            }
+            return Ok(());
+        }
+        if Self::within_role(nesting) {
+            // Inline tags wait for the next item.
+            return Ok(());
+        }
+
+        let breaks = match &self.newlines {
+            Some(Newlines {
+                paragraph: Some(_), ..
+            }) if nesting.open.iter().any(|x| x.paragraph) && newlines >= 2 => {
+                self.end_paragraph(nesting, out)?;
+                0
+            }
+            Some(Newlines {
+                paragraph: Some(_), ..
+            }) if self.paragraph_starts_here(nesting) => 0,
+            Some(_) => newlines,
+            None => 0,
+        };
+
+        // Tags not yet written are opened after the line breaks before them, the rest were open before those.
+        let (unwritten, reopened) = core::mem::take(&mut nesting.pending)
+            .into_iter()
+            .partition::<Vec<_>, _>(|x| x.unwritten);
+        for x in reopened {
+            if !x.model.block {
+                self.start_paragraph(nesting, out)?;
+            }
+            self.open_nested(nesting, x.token, false, out, policy)?;
+        }
+        self.start_paragraph(nesting, out)?;
+
+        if let Some(newlines) = &self.newlines {
+            let br = Token {
+                span: "",
+                start: 0,
+                kind: TokenKind::StandaloneBBTag(BBTag {
+                    tag: "br",
+                    args: "",
+                }),
+            };
+            for _ in 0..breaks {
+                newlines.line_break.standalone_tag(&self.writer, &br, out)?;
+            }
+        }
+
+        for x in unwritten {
+            self.open_nested(nesting, x.token, false, out, policy)?;
+        }
+        for x in core::mem::take(&mut nesting.deferred) {
+            self.writer.write_token(&x, out)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether inline content placed now would start a new paragraph.
+    fn paragraph_starts_here(&self, nesting: &Nesting<'_, '_, B, CustomTy>) -> bool {
+        matches!(
+            self.newlines,
+            Some(Newlines {
+                paragraph: Some(_),
+                ..
+            })
+        ) && nesting.open.last().map_or(true, |x| {
+            x.model.block && x.model.contains_blocks && x.model.role.is_none()
+        })
+    }
+
+    /// Open a paragraph, if inline content placed now would start one.
+    fn start_paragraph<'r, 'a>(
+        &'r self,
+        nesting: &mut Nesting<'r, 'a, B, CustomTy>,
+        out: &mut B::Output<'_>,
+    ) -> fmt::Result {
+        if !self.paragraph_starts_here(nesting) {
+            return Ok(());
+        }
+
+        let writer = self
+            .newlines
+            .as_ref()
+            .and_then(|x| x.paragraph.as_deref())
+            .unwrap();
+        let token = Token {
+            span: "",
+            start: 0,
+            kind: TokenKind::OpenBBTag(BBTag { tag: "p", args: "" }),
+        };
+
+        writer.open_tag(&self.writer, &token, out)?;
+        nesting.open.push(OpenTag {
+            token,
+            writer,
+            model: ContentModel::TEXT_BLOCK,
+            paragraph: true,
+            implicit: false,
+            unwritten: false,
+            suspended: Vec::new(),
+        });
+
+        Ok(())
+    }
+
+    /// Close the open paragraph, keeping the inline tags within it to be opened again in the next one.
+    fn end_paragraph<'r, 'a>(
+        &'r self,
+        nesting: &mut Nesting<'r, 'a, B, CustomTy>,
+        out: &mut B::Output<'_>,
+    ) -> fmt::Result {
+        let Some(idx) = nesting.open.iter().rposition(|x| x.paragraph) else {
+            return Ok(());
+        };
+
+        let closed = nesting.open.split_off(idx);
+        for x in closed.iter().rev() {
+            self.close_open(x, out)?;
+        }
+        nesting
+            .pending
+            .splice(0..0, closed.into_iter().filter(OpenTag::reopens));
+
+        Ok(())
+    }
+
+    /// Open the given tag, which has a writer, keeping to the content models of the tags it's within. Implicit tags are
+    /// opened by a standalone tag, and closed without a matching close tag.
+    /// Returns whether the tag was opened, rather than written out as text.
+    fn open_nested<'r, 'a>(
+        &'r self,
+        nesting: &mut Nesting<'r, 'a, B, CustomTy>,
+        token: Token<'a, CustomTy>,
+        implicit: bool,
+        out: &mut B::Output<'_>,
+        policy: &TagNamePolicy,
+    ) -> Result<bool, fmt::Error> {
+        let writer = self
+            .get_writer_for_tag_with(token.tag_name().unwrap(), policy)
+            .unwrap();
+        let model = writer.content_model(&token);
+
+        if Self::within_role(nesting) && !model.block && model.parent.is_none() {
+            // Opened once there's an item to open it in.
+            nesting.pending.push(OpenTag {
+                token,
+                writer,
+                model,
+                paragraph: false,
+                implicit,
+                unwritten: true,
+                suspended: Vec::new(),
+            });
+            return Ok(true);
+        }
+
+        let Some(mut suspended) = self.make_way(nesting, &model, out)? else {
+            self.write_content(nesting, &token, out, policy)?;
+            if !implicit {
+                nesting.degraded.push(token.start);
+            }
+            return Ok(false);
+        };
+
+        if model.parent.is_some() {
+            // Inline tags closed to end the previous item carry on into this one.
+            nesting.pending.splice(0..0, suspended);
+            suspended = Vec::new();
+        }
+
+        if !writer.open_tag(&self.writer, &token, out)? {
+            // Written out as text, so there's nothing to close, and whatever was closed to make way is opened again.
+            nesting.pending.splice(0..0, suspended);
+            if !implicit {
+                nesting.degraded.push(token.start);
+            }
+            return Ok(false);
+        }
+        nesting.open.push(OpenTag {
+            token,
+            writer,
+            model,
+            paragraph: false,
+            implicit,
+            unwritten: false,
+            suspended,
+        });
+
+        Ok(true)
+    }
+
+    /// Close any inline tags and paragraph which may not contain a tag with the given model, returning the inline tags.
+    /// For a tag with a [parent][ContentModel::parent], everything within the parent is closed instead, such as the
+    /// previous implicit list item.
+    /// Returns `None` if the tag still can't be placed, such as a heading within a heading, or a list item outside a list.
+    fn make_way<'r, 'a>(
+        &'r self,
+        nesting: &mut Nesting<'r, 'a, B, CustomTy>,
+        model: &ContentModel,
+        out: &mut B::Output<'_>,
+    ) -> Result<Option<Vec<OpenTag<'r, 'a, B, CustomTy>>>, fmt::Error> {
+        let idx = match model.parent {
+            Some(parent) => {
+                let Some(idx) = nesting
+                    .open
+                    .iter()
+                    .rposition(|x| x.model.role == Some(parent))
+                else {
+                    return Ok(None);
+                };
+                idx + 1
+            }
+            // Only a tag's own items may be placed directly within it.
+            None if Self::within_role(nesting) => return Ok(None),
+            None => {
+                let Some(idx) = nesting
+                    .open
+                    .iter()
+                    .position(|x| !x.model.may_contain(model))
+                else {
+                    return Ok(Some(Vec::new()));
+                };
+                idx
+            }
+        };
+
+        // Items are closed by the next item whether or not they were opened by a standalone tag.
+        if !nesting.open[idx..].iter().all(|x| {
+            !x.model.block
+                || x.paragraph
+                || x.implicit
+                || (model.parent.is_some() && x.model.parent == model.parent)
+        }) {
+            return Ok(None);
+        }
+
+        let closed = nesting.open.split_off(idx);
+        for x in closed.iter().rev() {
+            self.close_open(x, out)?;
+        }
+
+        Ok(Some(closed.into_iter().filter(OpenTag::reopens).collect()))
+    }
+
+    /// Close the open tag at the given index with the given close tag, closing anything opened since so the output stays
+    /// well nested.
+    fn close_nested<'r, 'a>(
+        &'r self,
+        nesting: &mut Nesting<'r, 'a, B, CustomTy>,
+        idx: usize,
+        close: &Token<'a, CustomTy>,
+        out: &mut B::Output<'_>,
+    ) -> fmt::Result {
+        let mut reopen = nesting.open.split_off(idx);
+        let closed = reopen.remove(0);
+        for x in reopen.iter().rev() {
+            self.close_open(x, out)?;
+        }
+
+        closed
+            .writer
+            .close_tag(&self.writer, &closed.token, close, out)?;
+
+        let reopen = reopen.into_iter().filter(OpenTag::reopens);
+        let pending = core::mem::take(&mut nesting.pending);
+        nesting.pending = closed
+            .suspended
+            .into_iter()
+            .chain(reopen)
+            .chain(pending)
+            .collect();
+
+        if closed.model.block {
+            nesting.newlines = 0;
+            nesting.after_block = true;
+        }
+
+        Ok(())
+    }
+
+    /// Find the open tag a close tag the parser couldn't match ends, if it's only unmatched because the tags opened
+    /// within it were left open, such as list items written as open tags instead of void tags, or were written out as
+    /// text. Only the items and the inline tags within them are closed along with it.
+    fn unmatched_close(
+        &self,
+        nesting: &Nesting<'_, '_, B, CustomTy>,
+        close: &Token<'_, CustomTy>,
+        policy: &TagNamePolicy,
+    ) -> Option<usize> {
+        let name = close.tag_name()?;
+        let idx = nesting
+            .open
+            .iter()
+            .rposition(|x| !x.paragraph && x.token.matches_tag(name, policy))?;
+
+        let rest = &nesting.open[(idx + 1)..];
+        let ends = match (
+            rest.iter().find(|x| !x.paragraph),
+            nesting.open[idx].model.role,
+        ) {
+            (None, _) => true,
+            (Some(first), Some(role)) => {
+                first.model.parent == Some(role)
+                    && rest.iter().all(|x| {
+                        !x.model.block || x.paragraph || x.implicit || x.model.parent == Some(role)
+                    })
+            }
+            (Some(_), None) => false,
+        };
+        ends.then_some(idx)
+    }
+
+    /// Close the given open tag without a matching close tag.
+    fn close_open(
+        &self,
+        open: &OpenTag<'_, '_, B, CustomTy>,
+        out: &mut B::Output<'_>,
+    ) -> fmt::Result {
+        let Token {
+            kind: TokenKind::OpenBBTag(tag_data, ..) | TokenKind::StandaloneBBTag(tag_data),
+            ..
+        } = &open.token
+        else {
+            unreachable!()
+        };
+
+        let fake_close = Token {
+            span: open.token.span,
+            start: open.token.start,
+            kind: TokenKind::CloseBBTag(tag_data.clone(), None),
+        };
+
+        open.writer
+            .close_tag(&self.writer, &open.token, &fake_close, out)
+    }
+
+    /// Register the provided tags to the renderer.
+    pub fn register_tags(&mut self, tags: &mut Vec<Box<dyn TagWriter<B, CustomTy>>>) {
+        self.tag_impls.append(tags);
+    }
+
+    /// Register the provided tag to the renderer.
+    pub fn register_tag(&mut self, tag: Box<dyn TagWriter<B, CustomTy>>) {
+        self.tag_impls.push(tag);
+    }
+
+    /// Attempt to locate the implementation for the given tag, if one exists.
+    pub fn get_writer_for_tag(&self, tag_name: &str) -> Option<&dyn TagWriter<B, CustomTy>> {
+        self.get_writer_for_tag_with(tag_name, &Default::default())
+    }
+
+    /// Attempt to locate the implementation for the given tag using the given name policy, if one exists.
+    pub fn get_writer_for_tag_with(
+        &self,
+        tag_name: &str,
+        policy: &TagNamePolicy,
+    ) -> Option<&dyn TagWriter<B, CustomTy>> {
+        if let Some(imp) = self.tag_cache.get(tag_name) {
+            return Some(self.tag_impls[*imp].as_ref());
+        }
+
+        let idx: Option<usize> = 'idx: {
+            for (idx, imp) in self.tag_impls.iter().enumerate() {
+                if imp.match_tag_with(tag_name, policy) {
+                    break 'idx Some(idx);
+                }
+            }
+            None
+        };
+
+        idx.map(|idx| self.tag_impls[idx].as_ref())
+    }
+}
+
+/// The tags opened while rendering, see [Renderer::render].
+struct Nesting<'r, 'a, B, CustomTy>
+where
+    B: Backend,
+    CustomTy: Clone + 'static,
+{
+    /// Tags opened by a writer and not yet closed, in order of opening.
+    open: Vec<OpenTag<'r, 'a, B, CustomTy>>,
+    /// Tags which were closed early, to be opened again before any following content.
+    pending: Vec<OpenTag<'r, 'a, B, CustomTy>>,
+    /// The starts of open tags which were written out as text, as they could not be placed.
+    degraded: Vec<usize>,
+    /// Content found directly within a tag with a role, to be written within its next item.
+    deferred: Vec<Token<'a, CustomTy>>,
+    /// The names of tags taking their contents which are known to have no close tag in the rest of the document.
+    unclosed: Vec<&'a str>,
+    /// Newlines in text not yet written, as they're dropped before blocks.
+    newlines: usize,
+    /// Whether a block was just opened or closed, so a newline directly after it is dropped.
+    after_block: bool,
+}
+
+/// A tag opened by a writer and not yet closed.
+struct OpenTag<'r, 'a, B, CustomTy>
+where
+    B: Backend,
+    CustomTy: Clone + 'static,
+{
+    token: Token<'a, CustomTy>,
+    writer: &'r dyn TagWriter<B, CustomTy>,
+    model: ContentModel,
+    /// Whether this is a paragraph opened for the newline policy, rather than a tag in the document.
+    paragraph: bool,
+    /// Whether this was opened by a standalone tag, such as `[*]`, so it's closed by the next one.
+    implicit: bool,
+    /// Whether this inline tag is waiting for content to be opened, and no block has come since its open tag.
+    unwritten: bool,
+    /// Inline tags which were closed to open this block, to be opened again once it's closed.
+    suspended: Vec<OpenTag<'r, 'a, B, CustomTy>>,
+}
+
+impl<B, CustomTy> OpenTag<'_, '_, B, CustomTy>
+where
+    B: Backend,
+    CustomTy: Clone + 'static,
+{
+    /// Whether this tag is opened again after being closed early. Paragraphs and tags which need a parent are not.
+    fn reopens(&self) -> bool {
+        !self.paragraph && self.model.parent.is_none()
+    }
+}
+
+/// Adapts a [std::io::Write] sink into a [fmt::Write] one, keeping the underlying error.
+/// # Remarks
+/// As [fmt::Error] carries no information, the last I/O error is kept in [IoAdapter::error] instead.
+#[derive(Debug)]
+pub struct IoAdapter<W> {
+    inner: W,
+    /// The last error produced by the underlying sink, if any.
+    pub error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoAdapter<W> {
+    /// Wrap the given sink.
+    pub fn new(inner: W) -> Self {
+        Self { inner, error: None }
+    }
+
+    /// Convert the result of writing through this adapter into an I/O result, using the kept error.
+    pub fn finish(self, result: fmt::Result) -> io::Result<W> {
+        match (result, self.error) {
+            (_, Some(error)) => Err(error),
+            (Ok(()), None) => Ok(self.inner),
+            (Err(_), None) => Err(io::Error::new(io::ErrorKind::Other, "formatter error")),
+        }
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
@@ -0,0 +1,295 @@
+//! Plain text extraction from a [BBParser]'s output, for search indexing, previews and email bodies.
+//!  All markup is dropped, with block level tags turned into line breaks.
+use alloc::{string::String, vec::Vec};
+
+use crate::{rules::builtin::NoParseRule, BBParser, BBTag, Token, TokenKind};
+
+/// Serializes a BBCode parse (from [BBParser]) to the plain text a reader would see, without any markup.
+#[derive(Clone, Debug)]
+pub struct TextSerializer<'a> {
+    /// Tags that start and end on their own line, such as headers and quotes.
+    pub block_tags: &'a [&'a str],
+    /// Tags that are replaced with a line break, such as `[br]`.
+    pub break_tags: &'a [&'a str],
+    /// Tags that contain quoted content. These are also treated as block tags.
+    pub quote_tags: &'a [&'a str],
+    /// Tags whose contents are not parsed, such as code.
+    pub noparse_tags: &'a [&'a str],
+    /// Whether or not to leave quoted content out of the output entirely.
+    pub omit_quotes: bool,
+    /// A prefix to write at the start of each quoted line, once per level of nesting, such as `"> "`.
+    pub quote_prefix: Option<&'a str>,
+    /// The width to word-wrap lines to, in characters, including quote prefixes.
+    /// Lines holding the contents of [TextSerializer::noparse_tags] are never wrapped, keeping their spacing.
+    pub wrap_width: Option<usize>,
+}
+
+impl<'a> Default for TextSerializer<'a> {
+    fn default() -> Self {
+        Self {
+            block_tags: &[
+                "h1",
+                "title",
+                "h2",
+                "topic",
+                "h3",
+                "subtopic",
+                "h4",
+                "h5",
+                "h6",
+                "pre",
+                "codeblock",
+                "center",
+                "left",
+                "right",
+                "list",
+                "ul",
+                "ol",
+                "li",
+                "*",
+                "table",
+                "tr",
+                "spoiler",
+                "hide",
+                "collapse",
+                "p",
+                "div",
+            ],
+            break_tags: &["br", "hr"],
+            quote_tags: &["quote", "blockquote"],
+            noparse_tags: &["code", "pre", "codeblock", "noparse"],
+            omit_quotes: false,
+            quote_prefix: None,
+            wrap_width: None,
+        }
+    }
+}
+
+impl<'a> TextSerializer<'a> {
+    /// Construct a serializer suited to email bodies, with quotes prefixed by `> ` and lines wrapped to the given width.
+    pub fn email(wrap_width: usize) -> Self {
+        Self {
+            quote_prefix: Some("> "),
+            wrap_width: Some(wrap_width),
+            ..Default::default()
+        }
+    }
+
+    /// Serialize the given BBCode 'document' out to plain text.
+    pub fn serialize<CustomTy>(&self, mut parser: BBParser<'_, CustomTy>) -> String
+    where
+        CustomTy: Clone + 'static,
+    {
+        let policy = parser.config().tag_names;
+        let is_any = |tags: &[&str], tk: &Token<'_, CustomTy>| {
+            tags.iter().any(|x| tk.matches_tag(x, &policy))
+        };
+
+        let mut lines = Lines::default();
+        // The names of the open quote tags, as tags left unclosed within a quote keep its close from being paired.
+        let mut quotes: Vec<&str> = Vec::new();
+        let mut verbatim = false;
+
+        while let Some(tk) = parser.next() {
+            let is_quote = is_any(self.quote_tags, &tk);
+
+            match tk.kind {
+                TokenKind::OpenBBTag(BBTag { tag, .. }) => {
+                    // Break tags are often written without a trailing `/`.
+                    if is_any(self.break_tags, &tk) {
+                        lines.hard_break();
+                    } else if is_quote {
+                        quotes.push(tag);
+                        lines.set_depth(quotes.len());
+                    } else if is_any(self.block_tags, &tk) {
+                        lines.break_line();
+                    }
+
+                    if is_any(self.noparse_tags, &tk) {
+                        parser.push_rule(NoParseRule::with_policy(tag, policy));
+                        verbatim = true;
+                    }
+                }
+                TokenKind::CloseBBTag(BBTag { tag, .. }, _) => {
+                    if is_any(self.noparse_tags, &tk) {
+                        verbatim = false;
+                    }
+
+                    if is_quote {
+                        if let Some(idx) = quotes.iter().rposition(|x| policy.names_match(x, tag)) {
+                            quotes.truncate(idx);
+                            lines.set_depth(quotes.len());
+                        }
+                    } else if is_any(self.block_tags, &tk) {
+                        lines.break_line();
+                    }
+                }
+                TokenKind::StandaloneBBTag(_) => {
+                    if is_any(self.break_tags, &tk) {
+                        lines.hard_break();
+                    } else if is_any(self.block_tags, &tk) {
+                        lines.break_line();
+                    }
+                }
+                TokenKind::Text if !self.omit_quotes || quotes.is_empty() => {
+                    lines.push_text(tk.span, verbatim)
+                }
+                _ => {}
+            }
+        }
+
+        self.finish(lines)
+    }
+
+    fn finish(&self, mut lines: Lines) -> String {
+        lines.break_line();
+
+        let mut out: Vec<String> = Vec::with_capacity(lines.done.len());
+        let mut last_blank = true;
+
+        for Line {
+            depth,
+            text,
+            verbatim,
+        } in lines.done.iter()
+        {
+            if self.omit_quotes && *depth > 0 {
+                continue;
+            }
+
+            let text = text.trim_end();
+            let blank = text.trim_start().is_empty();
+
+            // Collapse runs of blank lines, and never start with one.
+            if blank && last_blank {
+                continue;
+            }
+            last_blank = blank;
+
+            let mut prefix = String::new();
+            if let Some(p) = self.quote_prefix {
+                for _ in 0..*depth {
+                    prefix.push_str(p);
+                }
+            }
+
+            match self.wrap_width {
+                _ if blank => out.push(prefix.trim_end().into()),
+                // Wrapping would collapse the spacing of code.
+                Some(width) if !verbatim => {
+                    let width = width.saturating_sub(prefix.chars().count()).max(1);
+                    wrap(text, width, &prefix, &mut out);
+                }
+                _ => out.push(prefix + text),
+            }
+        }
+
+        if last_blank {
+            out.pop();
+        }
+
+        out.join("\n")
+    }
+}
+
+/// Greedily word-wraps the given line, writing out each wrapped line with the given prefix.
+fn wrap(text: &str, width: usize, prefix: &str, out: &mut Vec<String>) {
+    let mut line = String::from(prefix);
+    let mut col = 0;
+
+    for word in text.split_whitespace() {
+        let len = word.chars().count();
+
+        if col > 0 && col + 1 + len > width {
+            out.push(core::mem::replace(&mut line, String::from(prefix)));
+            col = 0;
+        }
+
+        if col > 0 {
+            line.push(' ');
+            col += 1;
+        }
+
+        line.push_str(word);
+        col += len;
+    }
+
+    out.push(line);
+}
+
+/// A finished line of text.
+struct Line {
+    /// The quote depth of the line.
+    depth: usize,
+    text: String,
+    /// Whether the line holds contents which weren't parsed, and so mustn't be wrapped.
+    verbatim: bool,
+}
+
+/// Finished lines of text, and the line currently being written.
+#[derive(Default)]
+struct Lines {
+    done: Vec<Line>,
+    current: String,
+    /// Whether the current line holds contents which weren't parsed.
+    verbatim: bool,
+    depth: usize,
+    /// Whether a block boundary just ended the line, so the newline written after it in the source can be skipped.
+    after_block: bool,
+}
+
+impl Lines {
+    /// End the current line, unless it is empty.
+    fn break_line(&mut self) {
+        if !self.current.trim().is_empty() {
+            self.hard_break();
+        } else {
+            self.current.clear();
+            self.verbatim = false;
+        }
+        self.after_block = true;
+    }
+
+    /// End the current line, even if it is empty.
+    fn hard_break(&mut self) {
+        self.done.push(Line {
+            depth: self.depth,
+            text: core::mem::take(&mut self.current),
+            verbatim: core::mem::take(&mut self.verbatim),
+        });
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.break_line();
+        self.depth = depth;
+    }
+
+    fn push_text(&mut self, text: &str, verbatim: bool) {
+        for (idx, line) in text.split('\n').enumerate() {
+            if idx > 0 {
+                if self.after_block && self.current.trim().is_empty() {
+                    self.current.clear();
+                    self.verbatim = false;
+                } else {
+                    self.hard_break();
+                }
+                self.after_block = false;
+            }
+
+            // Whitespace starting a line after a block was only there to separate it from the block.
+            let line = if self.after_block && self.current.is_empty() && !verbatim {
+                line.trim_start()
+            } else {
+                line
+            };
+            if !line.trim().is_empty() {
+                self.after_block = false;
+            }
+            self.verbatim |= verbatim;
+            self.current.push_str(line.trim_end_matches('\r'));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
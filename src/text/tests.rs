@@ -0,0 +1,86 @@
+use crate::{text::TextSerializer, BBParser};
+
+const BLOCKS: &str = "[h1]Title[/h1]\nSome [b]bold[/b] text.[br]Next line.\n[quote]Quoted [i]text[/i][/quote]\nAfter.";
+
+#[test]
+pub fn blocks() {
+    let serializer = TextSerializer::default();
+
+    assert_eq!(
+        serializer.serialize(BBParser::new(BLOCKS)),
+        "Title\nSome bold text.\nNext line.\nQuoted text\nAfter."
+    );
+}
+
+#[test]
+pub fn omit_quotes() {
+    let serializer = TextSerializer {
+        omit_quotes: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        serializer.serialize(BBParser::new(BLOCKS)),
+        "Title\nSome bold text.\nNext line.\nAfter."
+    );
+}
+
+const UNCLOSED_IN_QUOTE: &str = "[quote]x [b]y[/quote] my reply";
+
+#[test]
+pub fn unclosed_in_quote() {
+    // The bold tag is left open, so the parser can't pair the quote's close, but it still ends the quote.
+    let serializer = TextSerializer {
+        omit_quotes: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        serializer.serialize(BBParser::new(UNCLOSED_IN_QUOTE)),
+        "my reply"
+    );
+
+    let serializer = TextSerializer {
+        quote_prefix: Some("> "),
+        ..Default::default()
+    };
+    assert_eq!(
+        serializer.serialize(BBParser::new(UNCLOSED_IN_QUOTE)),
+        "> x y\nmy reply"
+    );
+}
+
+const NOPARSE: &str = "[code]let x = [b]y[/b];[/code]";
+
+#[test]
+pub fn noparse() {
+    let serializer = TextSerializer::default();
+
+    assert_eq!(
+        serializer.serialize(BBParser::new(NOPARSE)),
+        "let x = [b]y[/b];"
+    );
+}
+
+const EMAIL: &str = "[quote][quote]Deeply nested words go here[/quote]\n\nThe quick brown fox jumps over the lazy dog.[/quote]\nI agree with this entirely.";
+
+#[test]
+pub fn email() {
+    let serializer = TextSerializer::email(20);
+
+    assert_eq!(
+        serializer.serialize(BBParser::new(EMAIL)),
+        "> > Deeply nested\n> > words go here\n>\n> The quick brown\n> fox jumps over the\n> lazy dog.\nI agree with this\nentirely."
+    );
+}
+
+const EMAIL_CODE: &str = "Some words to wrap here.\n[pre]fn main() {\n    let  x = 1;\n}[/pre]";
+
+#[test]
+pub fn email_code() {
+    let serializer = TextSerializer::email(10);
+
+    assert_eq!(
+        serializer.serialize(BBParser::new(EMAIL_CODE)),
+        "Some words\nto wrap\nhere.\nfn main() {\n    let  x = 1;\n}"
+    );
+}
@@ -0,0 +1,163 @@
+//! Balanced truncation of BBCode documents, for excerpts such as thread listings.
+//!  Only visible text is counted, and every tag left open at the cut is closed again, so the output is still a valid document.
+use alloc::{string::String, vec::Vec};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{rules::builtin::NoParseRule, BBParser, BBTag, Token, TokenKind};
+
+/// Truncates a BBCode parse (from [BBParser]) to a maximum number of visible characters, producing BBCode.
+#[derive(Clone, Debug)]
+pub struct Truncator<'a> {
+    /// The maximum number of visible characters (extended grapheme clusters) to keep, not including the ellipsis.
+    pub max_chars: usize,
+    /// Text appended at the cut, before any closing tags.
+    pub ellipsis: &'a str,
+    /// Tags whose contents are not parsed, such as code. Their contents count as visible text.
+    pub noparse_tags: &'a [&'a str],
+    /// Tags whose contents are not shown, such as an image's URL. They count as no visible text, and are always kept
+    /// whole, as cutting them would change what they point to.
+    pub hidden_tags: &'a [&'a str],
+    /// Tags which show their contents as a link when written without a value, such as `[url]`. Such contents count as
+    /// visible text, but are kept whole or left out at the cut, as cutting them would change where the link goes.
+    pub link_tags: &'a [&'a str],
+}
+
+impl<'a> Default for Truncator<'a> {
+    fn default() -> Self {
+        Self {
+            max_chars: 200,
+            ellipsis: "…",
+            noparse_tags: &["code", "pre", "codeblock", "noparse"],
+            hidden_tags: &["img"],
+            link_tags: &["url", "email"],
+        }
+    }
+}
+
+impl<'a> Truncator<'a> {
+    /// Construct a truncator keeping at most the given number of visible characters.
+    pub fn new(max_chars: usize) -> Self {
+        Self {
+            max_chars,
+            ..Default::default()
+        }
+    }
+
+    /// Truncate the given BBCode 'document', returning it unchanged if it is short enough.
+    /// # Remarks
+    /// The cut is always placed directly after the last visible character kept, never inside a tag or grapheme.
+    /// Tags open at that point are closed in reverse order of opening, after the ellipsis.
+    pub fn truncate<'b, CustomTy>(&self, mut parser: BBParser<'b, CustomTy>) -> String
+    where
+        CustomTy: Clone + 'static,
+    {
+        let input = parser.input();
+        let policy = parser.config().tag_names;
+        let is_any = |tags: &[&str], tk: &Token<'_, CustomTy>| {
+            tags.iter().any(|x| tk.matches_tag(x, &policy))
+        };
+
+        let mut open: Vec<Token<'b, CustomTy>> = Vec::new();
+        // Tags open around the last visible character kept, and the byte position just after it.
+        let mut cut_open: Vec<Token<'b, CustomTy>> = Vec::new();
+        let mut last_kept = 0;
+        let mut count = 0;
+
+        let cut = 'cut: {
+            while let Some(tk) = parser.next() {
+                match tk.kind {
+                    TokenKind::OpenBBTag(BBTag { tag, .. })
+                        if is_any(self.hidden_tags, &tk)
+                            || (is_any(self.link_tags, &tk) && tk.arg_list().value().is_none()) =>
+                    {
+                        parser.push_rule(NoParseRule::with_policy(tag, policy));
+                        let hidden = is_any(self.hidden_tags, &tk);
+
+                        let mut contents = 0;
+                        let mut end = None;
+                        while let Some(next) = parser.next() {
+                            if let TokenKind::CloseBBTag(_, Some(idx)) = next.kind {
+                                if parser.closed_tags()[idx].start == tk.start {
+                                    end = Some(next.start + next.span.len());
+                                    break;
+                                }
+                            }
+                            if !hidden {
+                                contents += next.span.graphemes(true).count();
+                            }
+                        }
+
+                        // The whole tag is kept or left out, an unclosed one runs to the end of the input.
+                        if count + contents > self.max_chars {
+                            break 'cut last_kept;
+                        }
+                        count += contents;
+                        if let Some(end) = end {
+                            last_kept = end;
+                            cut_open.clone_from(&open);
+                        }
+                    }
+                    TokenKind::OpenBBTag(BBTag { tag, .. }) => {
+                        if is_any(self.noparse_tags, &tk) {
+                            parser.push_rule(NoParseRule::with_policy(tag, policy));
+                        }
+                        open.push(tk);
+                    }
+                    TokenKind::CloseBBTag(_, Some(idx)) => {
+                        let start = parser.closed_tags()[idx].start;
+                        open.retain(|x| x.start != start);
+                    }
+                    TokenKind::Text => {
+                        let mut kept = 0;
+
+                        for (offs, grapheme) in tk.span.grapheme_indices(true) {
+                            if count == self.max_chars {
+                                if kept > 0 {
+                                    cut_open.clone_from(&open);
+                                    break 'cut tk.start + offs;
+                                }
+                                break 'cut last_kept;
+                            }
+
+                            count += 1;
+                            kept = offs + grapheme.len();
+                        }
+
+                        if kept > 0 {
+                            last_kept = tk.start + kept;
+                            cut_open.clone_from(&open);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            // Everything fit.
+            return input.into();
+        };
+
+        let mut out = String::with_capacity(cut + self.ellipsis.len());
+        out.push_str(input[..cut].trim_end());
+        out.push_str(self.ellipsis);
+
+        for tk in cut_open.iter().rev() {
+            let (opener, closer) = parser
+                .config()
+                .brackets
+                .iter()
+                .find(|(o, c)| tk.span.starts_with(o) && tk.span.ends_with(c))
+                .cloned()
+                .unwrap_or(("[", "]"));
+
+            out.push_str(opener);
+            out.push('/');
+            out.push_str(tk.tag_name().unwrap());
+            out.push_str(closer);
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests;
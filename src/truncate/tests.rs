@@ -0,0 +1,62 @@
+use crate::{truncate::Truncator, BBParser};
+
+#[test]
+pub fn short_enough() {
+    let input = "[b]Short[/b] and sweet.";
+    assert_eq!(Truncator::new(16).truncate(BBParser::new(input)), input);
+}
+
+#[test]
+pub fn closes_in_reverse() {
+    let input = "[quote][b]Hello [i]there[/i] world[/b][/quote]";
+    assert_eq!(
+        Truncator::new(8).truncate(BBParser::new(input)),
+        "[quote][b]Hello [i]th…[/i][/b][/quote]"
+    );
+}
+
+#[test]
+pub fn cut_after_last_visible() {
+    // The cut lands before the [u] tag, so it is neither kept nor closed.
+    let input = "[b]Hello [/b][u]world[/u]";
+    assert_eq!(
+        Truncator::new(6).truncate(BBParser::new(input)),
+        "[b]Hello…[/b]"
+    );
+}
+
+#[test]
+pub fn graphemes() {
+    let input = "[b]e\u{301}e\u{301}e\u{301}[/b]";
+    assert_eq!(
+        Truncator::new(2).truncate(BBParser::new(input)),
+        "[b]e\u{301}e\u{301}…[/b]"
+    );
+}
+
+#[test]
+pub fn noparse() {
+    let input = "[code][b]a[/b][/code] b";
+    assert_eq!(
+        Truncator::new(4).truncate(BBParser::new(input)),
+        "[code][b]a…[/code]"
+    );
+}
+
+#[test]
+pub fn links_kept_whole() {
+    let input = "[img]https://example.com/a.png[/img] caption";
+    assert_eq!(Truncator::new(12).truncate(BBParser::new(input)), input);
+    assert_eq!(
+        Truncator::new(4).truncate(BBParser::new(input)),
+        "[img]https://example.com/a.png[/img] cap…"
+    );
+
+    let input =
+        "See [url]https://example.com/a[/url] or [url=https://example.com/b]this page[/url]";
+    assert_eq!(Truncator::new(10).truncate(BBParser::new(input)), "See…");
+    assert_eq!(
+        Truncator::new(34).truncate(BBParser::new(input)),
+        "See [url]https://example.com/a[/url] or [url=https://example.com/b]this…[/url]"
+    );
+}